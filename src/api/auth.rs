@@ -0,0 +1,117 @@
+use std::str::FromStr;
+
+use mongodb::bson::oid::ObjectId;
+
+use chrono::Utc;
+
+use crate::{
+    models::{Nonce, Session, SessionInfo, LoginUser},
+    utils::{CustomError, SiweMessage, AuthenticatedUser, issue_access_token},
+    configs::{AUTH_MODE, AuthMode},
+    repositories::{UserRepository, SessionRepository, NonceRepository}
+};
+
+/// Issues a new nonce and stores it.
+///
+/// Returns the nonce string, which the user must include in their SIWE message before it expires.
+pub async fn generate_nonce<N: NonceRepository + ?Sized>(nonces: &N) -> Result<String, CustomError> {
+    let nonce = Nonce::generate();
+    nonces.store_nonce(&nonce).await?;
+
+    Ok(nonce.nonce)
+}
+
+/// Verifies a SIWE (EIP-4361) login attempt.
+///
+/// Checks the signature against the rebuilt message, then consumes the nonce so that it can only ever back a single session.
+/// Returns the message's expiration date (in UNIX format), which the session inherits.
+pub async fn verify_siwe_login<N: NonceRepository + ?Sized>(nonces: &N, payload: &LoginUser) -> Result<i64, CustomError> {
+    let siwe_message = SiweMessage::from_login(payload);
+
+    // verifies that the signature was signed by `wallet_address` before trusting any of the other fields.
+    siwe_message.verify(&payload.signature)?;
+
+    // the nonce must have been issued by us and can only ever back a single session.
+    nonces.consume_nonce(&siwe_message.nonce).await?;
+
+    siwe_message.expiration_date()
+}
+
+/// Logs a user in via SIWE, creating their `User` account if it doesn't exist yet.
+///
+/// Existing users get their `updated_at` bumped. A fresh session instance is stored either way.
+///
+/// Returns the newly stored session instance and its bearer token if no errors occur.
+pub async fn login<N, U, S>(nonces: &N, users: &U, sessions: &S, payload: LoginUser) -> Result<(Session, String), CustomError>
+where
+    N: NonceRepository + ?Sized,
+    U: UserRepository + ?Sized,
+    S: SessionRepository + ?Sized,
+{
+    let expiration_date = verify_siwe_login(nonces, &payload).await?;
+
+    users.upsert_user(&payload.wallet_address).await?;
+
+    let (session, token) = Session::new(payload, expiration_date);
+    sessions.store_session(&session).await?;
+
+    Ok((session, token))
+}
+
+/// Issues a new signed access token, using the given session token as a refresh token.
+/// 
+/// This is the only point at which the `Sessions` collection is checked when `AUTH_MODE` is "token".
+pub async fn refresh_access_token<S: SessionRepository + ?Sized>(sessions: &S, refresh_token: &str) -> Result<String, CustomError> {
+    if *AUTH_MODE != AuthMode::Token {
+        return Err(CustomError::Auth("Access tokens are disabled.".to_string()));
+    }
+
+    let session = sessions.get_session_by_token_hash(&Session::hash_token(refresh_token)).await?
+        .ok_or(CustomError::Auth("Invalid refresh token.".to_string()))?;
+
+    if session.expiration_date <= Utc::now().timestamp() {
+        return Err(CustomError::Auth("Session has expired.".to_string()));
+    }
+
+    issue_access_token(&session)
+}
+
+/// Issues a signed access token for a newly created session if `AUTH_MODE` is "token".
+pub fn login_access_token(session: &Session) -> Result<Option<String>, CustomError> {
+    match *AUTH_MODE {
+        AuthMode::Token => Ok(Some(issue_access_token(session)?)),
+        AuthMode::Session => Ok(None)
+    }
+}
+
+/// Gets all of the authenticated user's active sessions, flagging the one used to make the current request.
+pub async fn get_sessions<S: SessionRepository + ?Sized>(sessions: &S, auth: &AuthenticatedUser) -> Result<Vec<SessionInfo>, CustomError> {
+    let active_sessions = sessions.get_active_sessions(&auth.wallet_address).await?;
+
+    Ok(active_sessions.iter().map(|session| SessionInfo::from_session(session, auth.session_id)).collect())
+}
+
+/// Revokes one of the authenticated user's sessions.
+/// 
+/// Returns a `Forbidden` error if the session belongs to another wallet, or a `NotFound` error if it doesn't exist.
+pub async fn revoke_session<S: SessionRepository + ?Sized>(sessions: &S, auth: &AuthenticatedUser, session_id: String) -> Result<(), CustomError> {
+    let session_id = ObjectId::from_str(&session_id)
+        .map_err(|e| CustomError::Validation(format!("Invalid session ID: {}", e)))?;
+
+    if sessions.revoke_session(session_id, &auth.wallet_address).await? {
+        return Ok(());
+    }
+
+    // the revocation itself is scoped to the wallet, so the session is only looked up to report why nothing was revoked.
+    match sessions.get_session(session_id).await? {
+        Some(_) => Err(CustomError::Forbidden("Not allowed to revoke another user's session.".to_string())),
+        None => Err(CustomError::NotFound("Session not found.".to_string()))
+    }
+}
+
+/// Logs the authenticated user out by revoking the session used to make the current request.
+pub async fn logout<S: SessionRepository + ?Sized>(sessions: &S, auth: &AuthenticatedUser) -> Result<(), CustomError> {
+    sessions.revoke_session(auth.session_id, &auth.wallet_address).await?;
+
+    Ok(())
+}
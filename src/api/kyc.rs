@@ -0,0 +1,109 @@
+use std::str::FromStr;
+
+use mongodb::bson::oid::ObjectId;
+
+use crate::{models::{KycSubmission, KycDocument, KycStatus, User}, utils::CustomError, repositories::{UserRepository, KycRepository, UserUpdate}};
+
+/// Submits a user's KYC documents for review.
+/// 
+/// Users can only have one pending submission at a time (enforced by a partial unique index, so concurrent submissions can't both succeed),
+/// and can't resubmit while their current verification is still valid.
+/// Returns the Object ID of the newly created submission.
+pub async fn submit_kyc<K, U>(kyc_submissions: &K, users: &U, wallet_address: String, documents: Vec<KycDocument>) -> Result<ObjectId, CustomError>
+where
+    K: KycRepository + ?Sized,
+    U: UserRepository + ?Sized,
+{
+    if documents.is_empty() {
+        return Err(CustomError::Validation("At least one document is required.".to_string()));
+    }
+
+    for document in &documents {
+        if document.document_type.trim().is_empty() || document.file_name.trim().is_empty() || document.file_hash.trim().is_empty() {
+            return Err(CustomError::Validation("document_type, file_name and file_hash are required for every document.".to_string()));
+        }
+    }
+
+    let user = users.get_user(&wallet_address).await?;
+
+    if user.is_kyc_valid() {
+        return Err(CustomError::Validation("User's KYC verification is still valid.".to_string()));
+    }
+
+    let submissions = kyc_submissions.get_submissions(&wallet_address).await?;
+
+    if submissions.iter().any(|submission| submission.status == KycStatus::Pending) {
+        return Err(pending_submission_error());
+    }
+
+    match kyc_submissions.store_submission(&KycSubmission::new(wallet_address, documents)).await {
+        Err(CustomError::Conflict(_)) => Err(pending_submission_error()),
+        result => result
+    }
+}
+
+/// Gets a user's profile (containing their KYC status) along with all of their KYC submissions, newest first.
+pub async fn get_kyc_status<K, U>(kyc_submissions: &K, users: &U, wallet_address: String) -> Result<(User, Vec<KycSubmission>), CustomError>
+where
+    K: KycRepository + ?Sized,
+    U: UserRepository + ?Sized,
+{
+    let user = users.get_user(&wallet_address).await?;
+    let submissions = kyc_submissions.get_submissions(&wallet_address).await?;
+
+    Ok((user, submissions))
+}
+
+/// Reviews a pending KYC submission.
+/// 
+/// On approval, the user's `kyc_verified` and `last_kyc_verification` fields are updated. A reason is required when rejecting.
+/// Owners can't review their own submissions.
+pub async fn review_kyc<K, U>(
+    kyc_submissions: &K,
+    users: &U,
+    submission_id: String,
+    reviewer: String,
+    approved: bool,
+    reason: Option<String>
+) -> Result<KycSubmission, CustomError>
+where
+    K: KycRepository + ?Sized,
+    U: UserRepository + ?Sized,
+{
+    let submission_id = ObjectId::from_str(&submission_id)
+        .map_err(|e| CustomError::Validation(format!("Invalid submission ID: {}", e)))?;
+
+    let reason = reason.filter(|reason| !reason.trim().is_empty());
+
+    if !approved && reason.is_none() {
+        return Err(CustomError::Validation("A reason is required when rejecting a KYC submission.".to_string()));
+    }
+
+    let submission = kyc_submissions.get_submission(submission_id).await?;
+
+    if submission.wallet_address.eq_ignore_ascii_case(&reviewer) {
+        return Err(CustomError::Forbidden("Owners cannot review their own KYC submission.".to_string()));
+    }
+
+    let status = match approved {
+        true => KycStatus::Approved,
+        false => KycStatus::Rejected,
+    };
+
+    let submission = kyc_submissions.review_submission(submission_id, status, reviewer, reason).await?;
+
+    if approved {
+        users.update_user(&submission.wallet_address, UserUpdate {
+            kyc_verified: Some(true),
+            last_kyc_verification: Some(submission.reviewed_at.unwrap_or_default()),
+            ..Default::default()
+        }).await?;
+    }
+
+    Ok(submission)
+}
+
+/// the error returned when a user submits KYC documents while they already have a pending submission
+fn pending_submission_error() -> CustomError {
+    CustomError::Conflict("User already has a pending KYC submission.".to_string())
+}
@@ -2,7 +2,7 @@ use std::str::FromStr;
 
 use ethers::{types::{U256, H256, H160}, utils::format_bytes32_string};
 
-use crate::utils::{LICENSE, LicenseAgreement};
+use crate::utils::{LICENSE, LicenseAgreement, CustomError};
 
 use super::get_license_base_terms;
 
@@ -13,12 +13,12 @@ use super::get_license_base_terms;
 pub async fn calculate_license_fee(
     permit: String,
     duration: U256,
-) -> Result<U256, String> {
+) -> Result<U256, CustomError> {
     // check if the permit exists, else throw an error.
-    let permit_exists = get_license_base_terms(permit.clone()).await != "".to_string();
+    let permit_exists = !get_license_base_terms(permit.clone()).await?.is_empty();
 
     if !permit_exists {
-        return Err("Specified permit does not exist".to_string())
+        return Err(CustomError::NotFound("Specified permit does not exist".to_string()))
     }
 
     // sample base fee rates and duration multipliers
@@ -37,8 +37,14 @@ pub async fn calculate_license_fee(
         (U256::from(63072000), 9), // 2 years in seconds
     ];
 
-    let base_fee = base_fee_rates.iter().find(|&&x| x.0 == permit).unwrap().1;
-    let duration_multiplier = duration_multipliers.iter().find(|&&x| x.0 == duration).unwrap().1;
+    let base_fee = base_fee_rates.iter()
+        .find(|&&x| x.0 == permit)
+        .ok_or(CustomError::Validation(format!("No base fee rate for permit {}", permit)))?
+        .1;
+    let duration_multiplier = duration_multipliers.iter()
+        .find(|&&x| x.0 == duration)
+        .ok_or(CustomError::Validation(format!("Unsupported license duration of {} seconds", duration)))?
+        .1;
 
     Ok(U256::from(base_fee * duration_multiplier))
 }
@@ -51,7 +57,7 @@ pub async fn pack_data(
     reporting_grace_period: U256,
     royalty_grace_period: U256,
     extra_data: U256
-) -> [U256; 2] {
+) -> Result<[U256; 2], CustomError> {
     let current_timestamp = U256::from(chrono::Utc::now().timestamp());
 
     let submission_date = current_timestamp;
@@ -72,7 +78,7 @@ pub async fn pack_data(
         untimely_reports,
         untimely_royalty_payments,
         extra_data
-    ).await.unwrap();
+    ).await?;
 
-    [packed_data.0, packed_data.1]
+    Ok([packed_data.0, packed_data.1])
 }
\ No newline at end of file
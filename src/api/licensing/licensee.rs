@@ -1,45 +1,243 @@
-use chrono::{DateTime, Utc};
-use ethers::{types::H160, utils::hex::{decode, encode_prefixed}};
+use chrono::{DateTime, Months, Utc};
+use ethers::{types::{H160, Bytes}, utils::hex::{decode, encode_prefixed}};
+use log::error;
+use mongodb::bson::oid::ObjectId;
 
-use crate::{utils::{LicenseeAccount, contract_base::LICENSE}, models::{LicenseeRaw, Licensee}};
+use crate::{utils::{LicenseeAccount, CustomError, is_valid_email, is_valid_e164_phone, is_valid_country_code, LICENSEE_DATA_VERSION, LICENSEE_DATA_VERSION_ENCRYPTED, LICENSEE_DATA_VERSION_COMMITMENT, encode_licensee_data, encode_licensee_commitment, decode_licensee_commitment, encrypt_licensee_data, decrypt_licensee_data, decode_licensee_data_v1, contract_base::{LICENSE, LicenseCall}}, models::{LicenseeRaw, Licensee, LicenseeStatus, LicenseeRecord, RegisterLicensee, UpdateLicensee, LicenseeBatchOutcome, LicenseeBatchStatus}, configs::{LICENSEE_ENCRYPTION_KEYS, LICENSEE_STORAGE_MODE, LICENSEE_MIN_AGE, LICENSEE_BATCH_SIZE, LICENSEE_RECEIPT_TIMEOUT, LicenseeStorageMode}, repositories::LicenseeRecordRepository};
 use std::str::FromStr;
+use tokio::time::{timeout_at, Instant};
+
+/// Builds the `data` that a user submits to `Licensee.sol - registerAccount` (via Metamask) to register as a licensee.
+/// 
+/// If `LICENSEE_STORAGE_MODE` is "commitment", the account information is stored in the database (as the user's pending record) and only a commitment to it is returned.
+pub async fn register_licensee_params<R: LicenseeRecordRepository + ?Sized>(licensee_records: &R, wallet_address: String, params: RegisterLicensee) -> Result<LicenseeRaw, CustomError> {
+    match *LICENSEE_STORAGE_MODE {
+        LicenseeStorageMode::Commitment => {
+            let licensee = params.into_licensee(wallet_address)?;
+            LicenseeRaw::register_account_commitment(licensee_records, licensee).await
+        },
+        LicenseeStorageMode::OnChain => LicenseeRaw::register_account_params(
+            wallet_address,
+            params.name,
+            params.dob,
+            params.address,
+            params.email,
+            params.phone,
+            params.company,
+            params.nationality,
+            params.country_of_application
+        )
+    }
+}
+
+impl RegisterLicensee {
+    /// Validates the registration parameters and converts them into a `Licensee` struct instance.
+    /// 
+    /// Every field is checked, so that a single `Validation` error can list everything that is wrong with the request.
+    /// Email addresses are lowercased and country codes uppercased.
+    pub fn into_licensee(self, wallet_address: String) -> Result<Licensee, CustomError> {
+        let mut errors = Vec::new();
+
+        let name = self.name.trim().to_string();
+        if name.is_empty() {
+            errors.push("name cannot be empty.".to_string());
+        }
+
+        let dob = match DateTime::parse_from_rfc3339(&self.dob) {
+            Ok(dob) => {
+                let dob = dob.with_timezone(&Utc);
+
+                // a licensee comes of age on their birthday (leap day births come of age on the 28th of February).
+                let adult_on = dob.date_naive().checked_add_months(Months::new(*LICENSEE_MIN_AGE * 12));
+                if adult_on.is_none_or(|adult_on| adult_on > Utc::now().date_naive()) {
+                    errors.push(format!("licensee must be at least {} years old.", *LICENSEE_MIN_AGE));
+                }
+
+                dob.timestamp()
+            },
+            Err(e) => {
+                errors.push(format!("dob is not RFC3339 compliant: {}.", e));
+                0
+            }
+        };
+
+        let address = self.address.trim().to_string();
+        if address.is_empty() {
+            errors.push("address cannot be empty.".to_string());
+        }
+
+        let email = self.email.trim().to_lowercase();
+        if !is_valid_email(&email) {
+            errors.push("email is not a valid email address.".to_string());
+        }
+
+        let phone = self.phone.trim().to_string();
+        if !is_valid_e164_phone(&phone) {
+            errors.push("phone is not E.164 compliant.".to_string());
+        }
+
+        let nationality = self.nationality.trim().to_uppercase();
+        if !is_valid_country_code(&nationality) {
+            errors.push("nationality is not an ISO 3166-1 alpha-2 country code.".to_string());
+        }
+
+        let country_of_application = self.country_of_application.trim().to_uppercase();
+        if !is_valid_country_code(&country_of_application) {
+            errors.push("country_of_application is not an ISO 3166-1 alpha-2 country code.".to_string());
+        }
+
+        if !errors.is_empty() {
+            return Err(CustomError::Validation(errors.join(" ")));
+        }
+
+        Ok(Licensee {
+            wallet_address: wallet_address.to_lowercase(),
+            name,
+            dob,
+            address,
+            email_address: email,
+            phone_number: phone,
+            company: self.company.map(|company| company.trim().to_string()).filter(|company| !company.is_empty()),
+            nationality,
+            country_of_application,
+            usable: false
+        })
+    }
+}
 
 impl Licensee {
     /// Gets a licensee account's data.
     /// 
     /// Retrieves the raw `LicenseeRaw` struct instance, then decodes the data into a `Licensee` struct instance.
-    pub async fn get_account_data(licensee_address: String) -> Result<Self, String> {
+    /// If the data is a commitment, the off-chain record it points to is fetched and verified against it instead.
+    /// 
+    /// Returns `None` if the address hasn't registered a licensee account.
+    pub async fn get_account_data<R: LicenseeRecordRepository + ?Sized>(licensee_records: &R, licensee_address: String) -> Result<Option<Self>, CustomError> {
+        let licensee_raw = LicenseeRaw::get_account_raw(licensee_address.clone()).await?;
+        let bytes = decode(&licensee_raw.data).map_err(|e| CustomError::Validation(format!("Error decoding data: {}", e)))?;
+
+        match bytes.first() {
+            Some(&LICENSEE_DATA_VERSION_COMMITMENT) => {
+                Licensee::resolve_commitment(licensee_records, &bytes[1..], licensee_raw.usable, &licensee_address).await.map(Some)
+            },
+            _ => Licensee::decode_licensee_data(licensee_raw.data, licensee_raw.usable, &licensee_address)
+        }
+    }
+
+    /// Gets a licensee account's status, without decoding its data.
+    pub async fn get_account_status(licensee_address: String) -> Result<LicenseeStatus, CustomError> {
         let licensee_raw = LicenseeRaw::get_account_raw(licensee_address).await?;
-        let decoded = Licensee::decode_licensee_data(licensee_raw.data, licensee_raw.usable)?;
 
-        Ok(decoded)
+        Ok(licensee_raw.status())
+    }
+
+    /// Returns the status of this (registered) licensee account.
+    pub fn status(&self) -> LicenseeStatus {
+        match self.usable {
+            true => LicenseeStatus::Approved,
+            false => LicenseeStatus::PendingApproval
+        }
+    }
+
+    /// Fetches the off-chain record that a commitment (see `encode_licensee_commitment`, excluding the version byte) points to,
+    /// and verifies that it still matches the commitment stored on-chain, confirming the record if it was pending.
+    /// 
+    /// Returns a `Tampered` error (and logs it) if the record has been tampered with.
+    async fn resolve_commitment<R: LicenseeRecordRepository + ?Sized>(
+        licensee_records: &R,
+        bytes: &[u8],
+        usable: bool,
+        licensee_address: &str
+    ) -> Result<Self, CustomError> {
+        let (record_id, commitment) = decode_licensee_commitment(bytes)?;
+        let record = licensee_records.get_record(record_id).await?;
+
+        if record.commitment()? != commitment || !record.wallet_address.eq_ignore_ascii_case(licensee_address) {
+            error!("Licensee record {} does not match its on-chain commitment for {}; it may have been tampered with.", record_id, licensee_address);
+
+            return Err(CustomError::Tampered(format!("Licensee record {} does not match its on-chain commitment.", record_id)));
+        }
+
+        // the record is now anchored on-chain, so it must no longer be replaced by the user's next pending record.
+        if record.pending {
+            licensee_records.confirm_record(record_id).await?;
+        }
+
+        Ok(Licensee { usable, ..record.licensee })
     }
 
     /// Decodes the result obtained from `Licensee.sol - getAccount` into a `Licensee` struct instance.
     /// 
-    /// Data should be a `bytes` type string that IS NOT empty and can be decoded using `ethers::utils::hex::decode`.
-    pub fn decode_licensee_data<T>(data: T, usable: bool) -> Result<Self, String>
+    /// Returns `None` if the data is empty, i.e. the address hasn't registered a licensee account.
+    /// Data should be a `bytes` type string that can be decoded using `ethers::utils::hex::decode`.
+    /// Both the versioned format (see `encode_licensee_data`) and the legacy pipe-separated format are supported.
+    /// 
+    /// Encrypted data (see `encrypt_licensee_data`) is decrypted with the matching key of `LICENSEE_ENCRYPTION_KEYS`; since the ciphertext is bound to the licensee's address,
+    /// `licensee_address` MUST be the account the data was read from.
+    pub fn decode_licensee_data<T>(data: T, usable: bool, licensee_address: &str) -> Result<Option<Self>, CustomError>
     where
         T: AsRef<[u8]>
     {
-        // if the data is empty, return a `Licensee` struct instance with all fields set to their default values.
-        if data.as_ref().len() == 0 {
-            return Ok(Licensee::default_licensee_data())
+        let decoded = decode(&data).map_err(|e| CustomError::Validation(format!("Error decoding data: {}", e)))?;
+
+        let licensee = match decoded.first() {
+            None => return Ok(None),
+            Some(&LICENSEE_DATA_VERSION) => decode_licensee_data_v1(&decoded[1..], usable),
+            Some(&LICENSEE_DATA_VERSION_ENCRYPTED) => Licensee::decrypt_licensee_data(&decoded[1..], usable, licensee_address),
+            Some(&LICENSEE_DATA_VERSION_COMMITMENT) => {
+                Err(CustomError::Validation("Licensee data is a commitment to an off-chain record; use `get_account_data` to resolve it.".to_string()))
+            },
+            Some(&version) if version.is_ascii_control() => {
+                Err(CustomError::Validation(format!("Unsupported licensee data version {}.", version)))
+            },
+            Some(_) => Licensee::decode_legacy_licensee_data(decoded, usable)
+        };
+
+        licensee.map(Some)
+    }
+
+    /// Decrypts licensee data encrypted by `encrypt_licensee_data` (excluding the version byte), then decodes it.
+    /// 
+    /// The decrypted data is in the versioned format (see `encode_licensee_data`), so it carries a version byte of its own.
+    fn decrypt_licensee_data(bytes: &[u8], usable: bool, licensee_address: &str) -> Result<Self, CustomError> {
+        let keyring = LICENSEE_ENCRYPTION_KEYS.as_ref()
+            .ok_or(CustomError::Validation("Licensee data is encrypted, but LICENSEE_ENCRYPTION_KEY is not configured.".to_string()))?;
+
+        let decrypted = decrypt_licensee_data(bytes, licensee_address, keyring)?;
+        let licensee = match decrypted.split_first() {
+            Some((&LICENSEE_DATA_VERSION, encoded)) => decode_licensee_data_v1(encoded, usable)?,
+            _ => return Err(CustomError::Validation("Decrypted licensee data has an unsupported version.".to_string()))
+        };
+
+        if !licensee.wallet_address.eq_ignore_ascii_case(licensee_address) {
+            return Err(CustomError::Validation("Decrypted licensee data does not belong to this account.".to_string()));
         }
 
-        let decoded = decode(&data).map_err(|e| format!("Error decoding data: {}", e.to_string()))?;
-        let decoded_str = String::from_utf8(decoded).map_err(|e| format!("Error converting bytes to string: {}", e.to_string()))?;
+        Ok(licensee)
+    }
+
+    /// Decodes licensee data stored in the legacy format, i.e. its fields joined by `|`.
+    /// 
+    /// NOTE: fields containing a `|` themselves can't be decoded correctly; these are why the versioned format exists.
+    fn decode_legacy_licensee_data(decoded: Vec<u8>, usable: bool) -> Result<Self, CustomError> {
+        let decoded_str = String::from_utf8(decoded).map_err(|e| CustomError::Validation(format!("Error converting bytes to string: {}", e)))?;
         let mut split = decoded_str.split('|');
+        let mut next_field = |field: &str| split.next()
+            .map(|x| x.to_string())
+            .ok_or(CustomError::Validation(format!("Licensee data is missing the {} field", field)));
 
-        let wallet_address = split.next().ok_or("")?.to_string();
-        let name = split.next().ok_or("")?.to_string();
-        let dob = DateTime::parse_from_rfc3339(&split.next().ok_or("")?).unwrap().with_timezone(&Utc).timestamp();
-        let address = split.next().ok_or("")?.to_string();
-        let email_address = split.next().ok_or("")?.to_string();
-        let phone_number = split.next().ok_or("")?.to_string();
-        let company = split.next().filter(|&x| x != "" && x != "None").map(|x| x.to_string());
-        let nationality = split.next().ok_or("")?.to_string();
-        let country_of_application = split.next().ok_or("")?.to_string();
+        let wallet_address = next_field("wallet_address")?;
+        let name = next_field("name")?;
+        let dob = DateTime::parse_from_rfc3339(&next_field("dob")?)
+            .map_err(|e| CustomError::Validation(format!("dob is NOT RFC3339 compliant: {}", e)))?
+            .with_timezone(&Utc)
+            .timestamp();
+        let address = next_field("address")?;
+        let email_address = next_field("email_address")?;
+        let phone_number = next_field("phone_number")?;
+        let company = next_field("company").ok().filter(|x| !x.is_empty() && x != "None");
+        let nationality = next_field("nationality")?;
+        let country_of_application = next_field("country_of_application")?;
 
         Ok(Licensee {
             wallet_address,
@@ -54,32 +252,14 @@ impl Licensee {
             usable
         })
     }
-
-    /// Returns a `Licensee` struct instance with all fields set to their default values.
-    /// 
-    /// This will be called when trying to get a licensee account that does not exist.
-    pub fn default_licensee_data() -> Self {
-        Licensee {
-            wallet_address: "".to_string(),
-            name: "".to_string(),
-            dob: Utc::now().timestamp(),
-            address: "".to_string(),
-            email_address: "".to_string(),
-            phone_number: "".to_string(),
-            company: None,
-            nationality: "".to_string(),
-            country_of_application: "".to_string(),
-            usable: false
-        }
-    }
 }
 
 impl LicenseeRaw {
-    /// Encodes the parameters into a `bytes` type string and returns a `LicenseeRaw` struct instance, containing the encoded data and a `usable` field.
+    /// Validates and encodes the parameters into a `bytes` type string (in the versioned format, see `encode_licensee_data`, encrypted if `LICENSEE_ENCRYPTION_KEY` is set) and returns a `LicenseeRaw` struct instance, containing the encoded data and a `usable` field.
     /// 
     /// Gets called when a user registers for a licensee account; the returned `LicenseeRaw` instance is to be submitted to Metamask via the frontend, calling `Licensee.sol - registerAccount`.
     /// 
-    /// NOTE: `dob` (i.e. date of birth) MUST be RFC3339 compliant, otherwise the function will panic.
+    /// NOTE: `dob` (i.e. date of birth) MUST be RFC3339 compliant; see `RegisterLicensee::into_licensee` for the rest of the validation.
     pub fn register_account_params(
         wallet_address: String,
         name: String,
@@ -90,34 +270,663 @@ impl LicenseeRaw {
         company: Option<String>,
         nationality: String,
         country_of_application: String
-    ) -> Self {
-        // if the `dob` parameter is NOT RFC3339 compliant, panic.
-        match DateTime::parse_from_rfc3339(&dob) {
-            Ok(_) => (),
-            Err(e) => panic!("dob parameter is NOT RFC3339 compliant: {}", e.to_string())
-        }
-
-        let concat = format!(
-            "{}|{}|{}|{}|{}|{}|{}|{}|{}",
-            wallet_address, name, dob, address, email, phone, company.unwrap_or_default(), nationality, country_of_application
-        );
-    
-        let data = encode_prefixed(concat.as_bytes());
-    
+    ) -> Result<Self, CustomError> {
+        let params = RegisterLicensee {
+            name,
+            dob,
+            address,
+            email,
+            phone,
+            company,
+            nationality,
+            country_of_application
+        };
+        let licensee = params.into_licensee(wallet_address)?;
+
+        Ok(LicenseeRaw::encode_account(&licensee))
+    }
+
+    /// Encodes a licensee's account information to be stored on-chain, according to `LICENSEE_STORAGE_MODE`
+    /// (i.e. either the encoded account information itself, or a commitment to an off-chain record of it).
+    /// 
+    /// Returns the `ObjectId` of the off-chain record along with the encoded data, if one was stored.
+    pub async fn from_licensee<R: LicenseeRecordRepository + ?Sized>(licensee_records: &R, licensee: Licensee) -> Result<(Self, Option<ObjectId>), CustomError> {
+        match *LICENSEE_STORAGE_MODE {
+            LicenseeStorageMode::Commitment => {
+                let record = LicenseeRecord::new(licensee);
+                let record_id = licensee_records.store_record(&record).await?;
+
+                Ok((LicenseeRaw::encode_commitment(&record, &record_id)?, Some(record_id)))
+            },
+            LicenseeStorageMode::OnChain => Ok((LicenseeRaw::encode_account(&licensee), None))
+        }
+    }
+
+    /// Encodes a licensee's account information into the versioned format (see `encode_licensee_data`).
+    fn encode_account(licensee: &Licensee) -> Self {
+        // if a server key is configured, the data is encrypted so that no PII is written on-chain in plaintext.
+        let encoded = encode_licensee_data(licensee);
+        let data = match LICENSEE_ENCRYPTION_KEYS.as_ref() {
+            Some(keyring) => encode_prefixed(encrypt_licensee_data(&encoded, &licensee.wallet_address, keyring)),
+            None => encode_prefixed(encoded)
+        };
+
         LicenseeRaw {
             data,
             usable: false
         }
     }
 
+    /// Stores a licensee's account information in the database and returns a `LicenseeRaw` struct instance whose data is a commitment to it
+    /// (see `encode_licensee_commitment`), so that no account information is written on-chain.
+    /// 
+    /// Like `register_account_params`, the returned instance is to be submitted to Metamask via the frontend, calling `Licensee.sol - registerAccount`.
+    /// Since the call is read-only, the record is stored as the licensee's pending record (see `store_pending_commitment`) rather than a new one,
+    /// so that repeated calls don't leave orphaned records behind.
+    /// 
+    /// Returns a `Conflict` error if the account is already registered, since its pending record may be the one its on-chain commitment points to.
+    pub async fn register_account_commitment<R: LicenseeRecordRepository + ?Sized>(licensee_records: &R, licensee: Licensee) -> Result<Self, CustomError> {
+        if LicenseeRaw::get_account_raw(licensee.wallet_address.clone()).await?.status() != LicenseeStatus::NotRegistered {
+            return Err(CustomError::Conflict("Licensee account is already registered.".to_string()));
+        }
+
+        LicenseeRaw::store_pending_commitment(licensee_records, licensee).await
+    }
+
+    /// Stores a licensee's account information as their pending record, replacing the previous one (see `LicenseeRecordRepository::upsert_pending_record`),
+    /// and returns a `LicenseeRaw` struct instance whose data is a commitment to it.
+    /// 
+    /// NOTE: only the commitment returned by the latest call resolves; earlier ones point to the same record, but no longer match it.
+    async fn store_pending_commitment<R: LicenseeRecordRepository + ?Sized>(licensee_records: &R, licensee: Licensee) -> Result<Self, CustomError> {
+        let record = LicenseeRecord::new_pending(licensee);
+        let record_id = licensee_records.upsert_pending_record(&record).await?;
+
+        LicenseeRaw::encode_commitment(&record, &record_id)
+    }
+
+    /// Returns a `LicenseeRaw` struct instance whose data is a commitment to the given record, stored under `record_id`.
+    fn encode_commitment(record: &LicenseeRecord, record_id: &ObjectId) -> Result<Self, CustomError> {
+        let commitment = record.commitment()?;
+
+        Ok(LicenseeRaw {
+            data: encode_prefixed(encode_licensee_commitment(record_id, &commitment)),
+            usable: false
+        })
+    }
+
+    /// Returns the status of the licensee account this data was read from.
+    /// 
+    /// `Licensee.sol` returns empty data for addresses that haven't registered an account.
+    pub fn status(&self) -> LicenseeStatus {
+        match (self.data.trim_start_matches("0x").is_empty(), self.usable) {
+            (true, _) => LicenseeStatus::NotRegistered,
+            (false, true) => LicenseeStatus::Approved,
+            (false, false) => LicenseeStatus::PendingApproval
+        }
+    }
+
     /// Calls `Licensee - getAccount` on the License contract and returns a LicenseeAccount struct instance.
-    pub async fn get_account_raw(licensee_address: String) -> Result<Self, String> {
-        let licensee = H160::from_str(&licensee_address).map_err(|e| format!("Error converting licensee address to H160: {}", e.to_string()))?;
-        let licensee_account: LicenseeAccount = LICENSE.get_account(licensee).await.map_err(|e| format!("Error getting licensee account: {}", e.to_string()))?;
+    pub async fn get_account_raw(licensee_address: String) -> Result<Self, CustomError> {
+        let licensee = H160::from_str(&licensee_address).map_err(|e| CustomError::Validation(format!("Error converting licensee address to H160: {}", e)))?;
+        let licensee_account: LicenseeAccount = LICENSE.get_account(licensee).await?;
 
         Ok(LicenseeRaw {
             data: licensee_account.data.to_string(),
             usable: licensee_account.usable
         })
     }
-}
\ No newline at end of file
+}
+
+/// Approves the given licensee accounts by calling `Licensee.sol - approveAccounts` through the `LICENSE` signer.
+/// 
+/// The licensees are split into batches of `LICENSEE_BATCH_SIZE`, each sent as its own transaction; see `send_licensee_batch`.
+pub async fn approve_accounts(licensees: Vec<String>) -> Result<Vec<LicenseeBatchOutcome>, CustomError> {
+    let licensees = parse_licensee_batch("licensees", &licensees)?;
+    let deadline = Instant::now() + *LICENSEE_RECEIPT_TIMEOUT;
+    let mut outcomes = Vec::new();
+
+    for (batch, chunk) in licensees.chunks(*LICENSEE_BATCH_SIZE).enumerate() {
+        outcomes.push(send_licensee_batch(batch, chunk, LICENSE.approve_accounts(chunk.to_vec()), deadline).await);
+    }
+
+    Ok(outcomes)
+}
+
+/// Updates the given licensee accounts by calling `Licensee.sol - updateAccounts` through the `LICENSE` signer.
+/// 
+/// Every account is validated before anything is stored or sent; the new account information is then encoded according to `LICENSEE_STORAGE_MODE`.
+/// The accounts are split into batches of `LICENSEE_BATCH_SIZE`, each sent as its own transaction; see `send_licensee_batch`.
+/// 
+/// Off-chain records (in commitment mode) have to exist before their commitments are sent, so they are stored up front;
+/// once a batch's outcome is known, either they or the records they supersede are deleted (see `settle_batch_records`).
+pub async fn update_accounts<R: LicenseeRecordRepository + ?Sized>(licensee_records: &R, accounts: Vec<UpdateLicensee>) -> Result<Vec<LicenseeBatchOutcome>, CustomError> {
+    let wallet_addresses: Vec<String> = accounts.iter().map(|account| account.wallet_address.clone()).collect();
+    let licensee_addresses = parse_licensee_batch("accounts", &wallet_addresses)?;
+
+    let mut licensees = Vec::with_capacity(accounts.len());
+    let mut errors = Vec::new();
+
+    for (index, account) in accounts.into_iter().enumerate() {
+        match account.params.into_licensee(account.wallet_address) {
+            Ok(licensee) => licensees.push(licensee),
+            Err(CustomError::Validation(e)) => errors.push(format!("accounts[{}]: {}", index, e)),
+            Err(e) => return Err(e)
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(CustomError::Validation(errors.join(" ")));
+    }
+
+    let mut data = Vec::with_capacity(licensees.len());
+    let mut record_ids = Vec::with_capacity(licensees.len());
+
+    for licensee in licensees {
+        let (licensee_raw, record_id) = LicenseeRaw::from_licensee(licensee_records, licensee).await?;
+        let bytes = decode(&licensee_raw.data).map_err(|e| CustomError::Validation(format!("Error decoding data: {}", e)))?;
+
+        data.push(Bytes::from(bytes));
+        record_ids.extend(record_id);
+    }
+
+    let deadline = Instant::now() + *LICENSEE_RECEIPT_TIMEOUT;
+    let mut outcomes = Vec::new();
+    let batches = licensee_addresses.chunks(*LICENSEE_BATCH_SIZE).zip(data.chunks(*LICENSEE_BATCH_SIZE));
+
+    for (batch, (chunk, data)) in batches.enumerate() {
+        let outcome = send_licensee_batch(batch, chunk, LICENSE.update_accounts(chunk.to_vec(), data.to_vec()), deadline).await;
+
+        // record IDs are only collected in commitment mode, in which case there is one per account.
+        if let Some(batch_ids) = record_ids.chunks(*LICENSEE_BATCH_SIZE).nth(batch) {
+            settle_batch_records(licensee_records, &outcome, chunk, batch_ids).await;
+        }
+
+        outcomes.push(outcome);
+    }
+
+    Ok(outcomes)
+}
+
+/// Removes the given licensee accounts by calling `Licensee.sol - removeAccounts` through the `LICENSE` signer.
+/// 
+/// The licensees are split into batches of `LICENSEE_BATCH_SIZE`, each sent as its own transaction; see `send_licensee_batch`.
+/// Once a batch is confirmed, the off-chain records (see `LICENSEE_STORAGE_MODE`) of its licensees are deleted as well.
+pub async fn remove_accounts<R: LicenseeRecordRepository + ?Sized>(licensee_records: &R, licensees: Vec<String>) -> Result<Vec<LicenseeBatchOutcome>, CustomError> {
+    let licensees = parse_licensee_batch("licensees", &licensees)?;
+    let deadline = Instant::now() + *LICENSEE_RECEIPT_TIMEOUT;
+    let mut outcomes = Vec::new();
+
+    for (batch, chunk) in licensees.chunks(*LICENSEE_BATCH_SIZE).enumerate() {
+        let outcome = send_licensee_batch(batch, chunk, LICENSE.remove_accounts(chunk.to_vec()), deadline).await;
+
+        if outcome.status == LicenseeBatchStatus::Confirmed {
+            for licensee in chunk {
+                if let Err(e) = licensee_records.delete_records(&format!("{:?}", licensee)).await {
+                    error!("Failed to delete the licensee records of {:?}: {}", licensee, e);
+                }
+            }
+        }
+
+        outcomes.push(outcome);
+    }
+
+    Ok(outcomes)
+}
+
+/// Parses a batch of licensee addresses, rejecting empty batches, malformed addresses and duplicates.
+/// 
+/// `field` is the name of the request field the addresses came from, used in the error messages.
+fn parse_licensee_batch(field: &str, licensees: &[String]) -> Result<Vec<H160>, CustomError> {
+    if licensees.is_empty() {
+        return Err(CustomError::Validation(format!("{} cannot be empty.", field)));
+    }
+
+    let mut parsed = Vec::with_capacity(licensees.len());
+    let mut errors = Vec::new();
+
+    for (index, licensee) in licensees.iter().enumerate() {
+        match H160::from_str(licensee) {
+            Ok(address) if parsed.contains(&address) => errors.push(format!("{}[{}] ({}) is a duplicate.", field, index, licensee)),
+            Ok(address) => parsed.push(address),
+            Err(_) => errors.push(format!("{}[{}] ({}) is not a valid address.", field, index, licensee))
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(CustomError::Validation(errors.join(" ")));
+    }
+
+    Ok(parsed)
+}
+
+/// Cleans up the off-chain records of a batch sent by `update_accounts`, where `record_ids` are the new records of `licensees` (in order).
+/// 
+/// If the batch failed or reverted, the new records are deleted, since nothing points to them on-chain; if it was confirmed,
+/// every older record of its licensees is deleted instead, since their on-chain commitments now point to the new ones.
+/// Records of submitted batches are kept, as their transactions may still be mined.
+/// Errors are only logged, so that the outcomes of the remaining batches are still reported.
+async fn settle_batch_records<R: LicenseeRecordRepository + ?Sized>(
+    licensee_records: &R,
+    outcome: &LicenseeBatchOutcome,
+    licensees: &[H160],
+    record_ids: &[ObjectId]
+) {
+    match outcome.status {
+        LicenseeBatchStatus::Failed | LicenseeBatchStatus::Reverted => {
+            if let Err(e) = licensee_records.delete_records_by_id(record_ids).await {
+                error!("Failed to delete the licensee records of batch {}: {}", outcome.batch, e);
+            }
+        },
+        LicenseeBatchStatus::Confirmed => {
+            for (licensee, record_id) in licensees.iter().zip(record_ids) {
+                if let Err(e) = licensee_records.delete_superseded_records(&format!("{:?}", licensee), *record_id).await {
+                    error!("Failed to delete the superseded licensee records of {:?}: {}", licensee, e);
+                }
+            }
+        },
+        LicenseeBatchStatus::Submitted => ()
+    }
+}
+
+/// Sends a single batch transaction and waits (until `deadline`) for it to be mined.
+/// 
+/// Batches are sent one after another (rather than concurrently) so that the signer's nonces stay in order;
+/// a failed batch doesn't stop the remaining ones from being sent, since its outcome is reported instead.
+/// The deadline is shared by every batch of a request (see `LICENSEE_RECEIPT_TIMEOUT`), so that the request is bounded
+/// regardless of how many batches it has; batches still unmined by then are reported as submitted, along with their transaction hash.
+async fn send_licensee_batch(batch: usize, licensees: &[H160], call: LicenseCall, deadline: Instant) -> LicenseeBatchOutcome {
+    let mut outcome = LicenseeBatchOutcome {
+        batch,
+        licensees: licensees.iter().map(|licensee| format!("{:?}", licensee)).collect(),
+        tx_hash: None,
+        status: LicenseeBatchStatus::Failed,
+        error: None,
+        revert: None
+    };
+
+    let pending = match call.send().await {
+        Ok(pending) => pending,
+        Err(e) => {
+            let e = CustomError::from(e);
+            error!("Licensee batch {} failed to send: {}", batch, e);
+
+            if let CustomError::ContractRevert(revert) = &e {
+                outcome.revert = Some(revert.clone());
+            }
+
+            outcome.error = Some(e.to_string());
+            return outcome;
+        }
+    };
+
+    outcome.tx_hash = Some(format!("{:?}", pending.tx_hash()));
+
+    match timeout_at(deadline, pending).await {
+        Ok(Ok(Some(receipt))) if receipt.status == Some(1.into()) => outcome.status = LicenseeBatchStatus::Confirmed,
+        Ok(Ok(Some(_))) => {
+            outcome.status = LicenseeBatchStatus::Reverted;
+            outcome.error = Some("Transaction reverted.".to_string());
+        },
+        Ok(Ok(None)) => outcome.error = Some("Transaction was dropped from the mempool.".to_string()),
+        // the transaction was sent, so it may still be mined even though its receipt couldn't be fetched.
+        Ok(Err(e)) => {
+            outcome.status = LicenseeBatchStatus::Submitted;
+            outcome.error = Some(CustomError::Rpc(e.to_string()).to_string());
+        },
+        Err(_) => outcome.status = LicenseeBatchStatus::Submitted
+    }
+
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Days, Months, SecondsFormat, TimeZone, Utc};
+    use ethers::utils::hex::{decode, encode_prefixed};
+
+    use std::collections::HashMap;
+
+    use ethers::types::H160;
+    use tokio::time::Instant;
+
+    use crate::{
+        models::{Licensee, LicenseeRaw, LicenseeRecord, RegisterLicensee, LicenseeBatchOutcome, LicenseeBatchStatus},
+        utils::{
+            encode_licensee_data, encrypt_licensee_data, decode_licensee_commitment, CustomError, LICENSEE_DATA_VERSION_ENCRYPTED, contract_base::LICENSE,
+            testing::{init_test_env, TEST_ENCRYPTION_KEY_ID, TEST_RETIRED_ENCRYPTION_KEY}
+        },
+        configs::{LICENSEE_MIN_AGE, LicenseeKeyring},
+        repositories::{InMemoryLicenseeRecordRepository, LicenseeRecordRepository}
+    };
+
+    use super::{settle_batch_records, send_licensee_batch};
+
+    /// the wallet address that the test data belongs to
+    const WALLET_ADDRESS: &str = "0x8ba1f109551bd432803012645ac136ddd64dba72";
+
+    fn licensee(company: Option<&str>) -> Licensee {
+        Licensee {
+            wallet_address: WALLET_ADDRESS.to_string(),
+            name: "Jane Doe".to_string(),
+            dob: 631152000,
+            address: "1 Main Street".to_string(),
+            email_address: "jane@example.com".to_string(),
+            phone_number: "+6512345678".to_string(),
+            company: company.map(|company| company.to_string()),
+            nationality: "SG".to_string(),
+            country_of_application: "MY".to_string(),
+            usable: true,
+        }
+    }
+
+    /// Builds registration parameters for a licensee born on the given date (RFC3339).
+    fn register_params(dob: String) -> RegisterLicensee {
+        RegisterLicensee {
+            name: " Jane Doe ".to_string(),
+            dob,
+            address: "1 Main Street".to_string(),
+            email: "Jane@Example.com".to_string(),
+            phone: "+6512345678".to_string(),
+            company: Some(" ".to_string()),
+            nationality: "sg".to_string(),
+            country_of_application: "my".to_string(),
+        }
+    }
+
+    /// Returns the RFC3339 date of birth of someone who turns `LICENSEE_MIN_AGE` today, offset by `days`.
+    fn dob_of_age_today(days: i64) -> String {
+        let today = Utc::now().date_naive();
+        let dob = today.checked_sub_months(Months::new(*LICENSEE_MIN_AGE * 12)).unwrap();
+        let dob = match days >= 0 {
+            true => dob.checked_add_days(Days::new(days as u64)).unwrap(),
+            false => dob.checked_sub_days(Days::new(days.unsigned_abs())).unwrap()
+        };
+
+        Utc.from_utc_datetime(&dob.and_hms_opt(0, 0, 0).unwrap()).to_rfc3339_opts(SecondsFormat::Secs, true)
+    }
+
+    /// Decodes the commitment data of a `LicenseeRaw` struct instance, excluding the version byte.
+    fn commitment_bytes(licensee_raw: &LicenseeRaw) -> Vec<u8> {
+        decode(&licensee_raw.data).unwrap()[1..].to_vec()
+    }
+
+    /// Builds the outcome of the first batch with the given status.
+    fn batch_outcome(status: LicenseeBatchStatus) -> LicenseeBatchOutcome {
+        LicenseeBatchOutcome {
+            batch: 0,
+            licensees: vec![WALLET_ADDRESS.to_string()],
+            tx_hash: None,
+            status,
+            error: None,
+            revert: None
+        }
+    }
+
+    /// Encodes `fields` the way licensee data was stored before the versioned format, i.e. joined by `|`.
+    fn legacy_data(fields: &[&str]) -> String {
+        encode_prefixed(fields.join("|"))
+    }
+
+    #[test]
+    fn decodes_versioned_licensee_data() {
+        let data = encode_prefixed(encode_licensee_data(&licensee(Some("Doe | Sons"))));
+
+        let decoded = Licensee::decode_licensee_data(data, true, WALLET_ADDRESS).unwrap();
+        assert_eq!(decoded, Some(licensee(Some("Doe | Sons"))));
+    }
+
+    #[test]
+    fn decodes_legacy_pipe_separated_licensee_data() {
+        let data = legacy_data(&[
+            WALLET_ADDRESS, "Jane Doe", "1990-01-01T00:00:00Z", "1 Main Street", "jane@example.com", "+6512345678", "Doe Holdings", "SG", "MY"
+        ]);
+
+        let decoded = Licensee::decode_licensee_data(data, true, WALLET_ADDRESS).unwrap();
+        assert_eq!(decoded, Some(licensee(Some("Doe Holdings"))));
+    }
+
+    #[test]
+    fn decodes_legacy_licensee_data_without_a_company() {
+        for company in ["", "None"] {
+            let data = legacy_data(&[
+                WALLET_ADDRESS, "Jane Doe", "1990-01-01T00:00:00Z", "1 Main Street", "jane@example.com", "+6512345678", company, "SG", "MY"
+            ]);
+
+            let decoded = Licensee::decode_licensee_data(data, true, WALLET_ADDRESS).unwrap();
+            assert_eq!(decoded, Some(licensee(None)));
+        }
+    }
+
+    #[test]
+    fn legacy_licensee_data_missing_a_field_is_rejected() {
+        let data = legacy_data(&[WALLET_ADDRESS, "Jane Doe", "1990-01-01T00:00:00Z", "1 Main Street", "jane@example.com", "+6512345678"]);
+
+        match Licensee::decode_licensee_data(data, true, WALLET_ADDRESS) {
+            Err(CustomError::Validation(e)) => assert!(e.contains("nationality"), "{}", e),
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn legacy_licensee_data_misreads_fields_containing_pipes() {
+        // the reason the versioned format exists: a `|` inside a field shifts every field after it.
+        let data = legacy_data(&[
+            WALLET_ADDRESS, "Jane Doe", "1990-01-01T00:00:00Z", "1 Main Street", "jane@example.com", "+6512345678", "Doe | Sons", "SG", "MY"
+        ]);
+
+        let decoded = Licensee::decode_licensee_data(data, true, WALLET_ADDRESS).unwrap().unwrap();
+        assert_eq!(decoded.company.as_deref(), Some("Doe "));
+        assert_eq!(decoded.nationality, " Sons");
+    }
+
+    #[test]
+    fn encrypted_licensee_data_round_trips() {
+        init_test_env();
+
+        let data = LicenseeRaw::encode_account(&licensee(Some("Doe | Sons"))).data;
+        assert_eq!(decode(&data).unwrap()[..2], [LICENSEE_DATA_VERSION_ENCRYPTED, TEST_ENCRYPTION_KEY_ID]);
+
+        let decoded = Licensee::decode_licensee_data(data, true, WALLET_ADDRESS).unwrap();
+        assert_eq!(decoded, Some(licensee(Some("Doe | Sons"))));
+    }
+
+    #[test]
+    fn licensee_data_encrypted_before_a_key_rotation_is_decoded() {
+        init_test_env();
+
+        let retired_key: [u8; 32] = decode(TEST_RETIRED_ENCRYPTION_KEY).unwrap().try_into().unwrap();
+        let keyring = LicenseeKeyring::new(0, retired_key, HashMap::new());
+        let data = encode_prefixed(encrypt_licensee_data(&encode_licensee_data(&licensee(None)), WALLET_ADDRESS, &keyring));
+
+        let decoded = Licensee::decode_licensee_data(data, true, WALLET_ADDRESS).unwrap();
+        assert_eq!(decoded, Some(licensee(None)));
+    }
+
+    #[test]
+    fn encrypted_licensee_data_of_another_account_is_rejected() {
+        init_test_env();
+
+        let data = LicenseeRaw::encode_account(&licensee(None)).data;
+
+        assert!(Licensee::decode_licensee_data(data, true, "0x0000000000000000000000000000000000000001").is_err());
+    }
+
+    #[test]
+    fn empty_licensee_data_is_not_registered() {
+        assert_eq!(Licensee::decode_licensee_data("0x", false, WALLET_ADDRESS).unwrap(), None);
+    }
+
+    #[test]
+    fn unknown_licensee_data_versions_are_rejected() {
+        assert!(Licensee::decode_licensee_data(encode_prefixed([9_u8, 0, 0]), false, WALLET_ADDRESS).is_err());
+    }
+
+    #[test]
+    fn registration_params_are_normalized() {
+        let licensee = register_params(dob_of_age_today(-1)).into_licensee(WALLET_ADDRESS.to_uppercase().replace("0X", "0x")).unwrap();
+
+        assert_eq!(licensee.wallet_address, WALLET_ADDRESS);
+        assert_eq!(licensee.name, "Jane Doe");
+        assert_eq!(licensee.email_address, "jane@example.com");
+        assert_eq!(licensee.company, None);
+        assert_eq!(licensee.nationality, "SG");
+        assert_eq!(licensee.country_of_application, "MY");
+        assert!(!licensee.usable);
+    }
+
+    #[test]
+    fn licensees_come_of_age_on_their_birthday() {
+        assert!(register_params(dob_of_age_today(0)).into_licensee(WALLET_ADDRESS.to_string()).is_ok());
+
+        match register_params(dob_of_age_today(1)).into_licensee(WALLET_ADDRESS.to_string()) {
+            Err(CustomError::Validation(e)) => assert!(e.contains(&format!("at least {} years old", *LICENSEE_MIN_AGE)), "{}", e),
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn registration_errors_are_reported_together() {
+        let params = RegisterLicensee {
+            email: "jane".to_string(),
+            phone: "12345".to_string(),
+            nationality: "XX".to_string(),
+            ..register_params("1990-01-01".to_string())
+        };
+
+        match params.into_licensee(WALLET_ADDRESS.to_string()) {
+            Err(CustomError::Validation(e)) => {
+                for field in ["dob", "email", "phone", "nationality"] {
+                    assert!(e.contains(field), "{} is missing from: {}", field, e);
+                }
+
+                assert!(!e.contains("country_of_application"), "{}", e);
+            },
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn pending_commitments_replace_the_previous_pending_record() {
+        let records = InMemoryLicenseeRecordRepository::new();
+
+        let first = commitment_bytes(&LicenseeRaw::store_pending_commitment(&records, licensee(None)).await.unwrap());
+        let second = commitment_bytes(&LicenseeRaw::store_pending_commitment(&records, licensee(Some("Doe Holdings"))).await.unwrap());
+
+        let (first_id, _) = decode_licensee_commitment(&first).unwrap();
+        let (second_id, _) = decode_licensee_commitment(&second).unwrap();
+        assert_eq!(first_id, second_id);
+        assert!(records.get_record(first_id).await.unwrap().pending);
+
+        // only the latest commitment matches the record.
+        assert!(Licensee::resolve_commitment(&records, &first, false, WALLET_ADDRESS).await.is_err());
+
+        let resolved = Licensee::resolve_commitment(&records, &second, false, WALLET_ADDRESS).await.unwrap();
+        assert_eq!(resolved, Licensee { usable: false, ..licensee(Some("Doe Holdings")) });
+    }
+
+    #[tokio::test]
+    async fn tampered_records_are_reported_as_such() {
+        let records = InMemoryLicenseeRecordRepository::new();
+
+        let registered = commitment_bytes(&LicenseeRaw::store_pending_commitment(&records, licensee(None)).await.unwrap());
+        let (record_id, _) = decode_licensee_commitment(&registered).unwrap();
+
+        let mut record = records.get_record(record_id).await.unwrap();
+        record.licensee.name = "John Doe".to_string();
+        records.store_record(&record).await.unwrap();
+
+        let result = Licensee::resolve_commitment(&records, &registered, true, WALLET_ADDRESS).await;
+        assert!(matches!(&result, Err(CustomError::Tampered(e)) if e.contains(&record_id.to_hex())), "{:?}", result);
+    }
+
+    #[tokio::test]
+    async fn records_of_another_wallet_are_reported_as_tampered() {
+        let records = InMemoryLicenseeRecordRepository::new();
+        let registered = commitment_bytes(&LicenseeRaw::store_pending_commitment(&records, licensee(None)).await.unwrap());
+
+        let result = Licensee::resolve_commitment(&records, &registered, true, "0x0000000000000000000000000000000000000001").await;
+        assert!(matches!(result, Err(CustomError::Tampered(_))), "{:?}", result);
+    }
+
+    #[tokio::test]
+    async fn resolved_records_are_confirmed_and_no_longer_replaced() {
+        let records = InMemoryLicenseeRecordRepository::new();
+
+        let registered = commitment_bytes(&LicenseeRaw::store_pending_commitment(&records, licensee(None)).await.unwrap());
+        let (record_id, _) = decode_licensee_commitment(&registered).unwrap();
+
+        Licensee::resolve_commitment(&records, &registered, true, WALLET_ADDRESS).await.unwrap();
+        assert!(!records.get_record(record_id).await.unwrap().pending);
+
+        let next = commitment_bytes(&LicenseeRaw::store_pending_commitment(&records, licensee(Some("Doe Holdings"))).await.unwrap());
+        let (next_id, _) = decode_licensee_commitment(&next).unwrap();
+        assert_ne!(next_id, record_id);
+
+        let resolved = Licensee::resolve_commitment(&records, &registered, true, WALLET_ADDRESS).await.unwrap();
+        assert_eq!(resolved, licensee(None));
+    }
+
+    #[tokio::test]
+    async fn records_of_unwritten_batches_are_discarded() {
+        let records = InMemoryLicenseeRecordRepository::new();
+        let licensees = [WALLET_ADDRESS.parse::<H160>().unwrap()];
+        let previous_id = records.store_record(&LicenseeRecord::new(licensee(None))).await.unwrap();
+
+        for status in [LicenseeBatchStatus::Failed, LicenseeBatchStatus::Reverted] {
+            let record_id = records.store_record(&LicenseeRecord::new(licensee(None))).await.unwrap();
+            settle_batch_records(&records, &batch_outcome(status), &licensees, &[record_id]).await;
+
+            assert!(matches!(records.get_record(record_id).await, Err(CustomError::NotFound(_))), "{:?}", status);
+            assert!(records.get_record(previous_id).await.is_ok(), "{:?}", status);
+        }
+    }
+
+    #[tokio::test]
+    async fn records_of_submitted_batches_are_kept() {
+        let records = InMemoryLicenseeRecordRepository::new();
+        let licensees = [WALLET_ADDRESS.parse::<H160>().unwrap()];
+        let previous_id = records.store_record(&LicenseeRecord::new(licensee(None))).await.unwrap();
+        let record_id = records.store_record(&LicenseeRecord::new(licensee(None))).await.unwrap();
+
+        settle_batch_records(&records, &batch_outcome(LicenseeBatchStatus::Submitted), &licensees, &[record_id]).await;
+
+        assert!(records.get_record(previous_id).await.is_ok());
+        assert!(records.get_record(record_id).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn only_the_latest_record_remains_after_a_confirmed_update() {
+        let records = InMemoryLicenseeRecordRepository::new();
+        let other_wallet = "0x0000000000000000000000000000000000000001";
+        let other_licensee = Licensee { wallet_address: other_wallet.to_string(), ..licensee(None) };
+
+        let registered_id = records.store_record(&LicenseeRecord::new(licensee(None))).await.unwrap();
+        let pending_id = records.upsert_pending_record(&LicenseeRecord::new_pending(licensee(None))).await.unwrap();
+        let other_id = records.store_record(&LicenseeRecord::new(other_licensee)).await.unwrap();
+        let updated_id = records.store_record(&LicenseeRecord::new(licensee(Some("Doe Holdings")))).await.unwrap();
+
+        let licensees = [WALLET_ADDRESS.parse::<H160>().unwrap()];
+        settle_batch_records(&records, &batch_outcome(LicenseeBatchStatus::Confirmed), &licensees, &[updated_id]).await;
+
+        for record_id in [registered_id, pending_id] {
+            assert!(matches!(records.get_record(record_id).await, Err(CustomError::NotFound(_))));
+        }
+
+        assert_eq!(records.get_record(updated_id).await.unwrap().licensee.company.as_deref(), Some("Doe Holdings"));
+        assert!(records.get_record(other_id).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn batches_that_fail_to_send_are_reported_as_failed() {
+        init_test_env();
+
+        let licensees = [WALLET_ADDRESS.parse::<H160>().unwrap()];
+        let outcome = send_licensee_batch(3, &licensees, LICENSE.approve_accounts(licensees.to_vec()), Instant::now()).await;
+
+        assert_eq!(outcome.batch, 3);
+        assert_eq!(outcome.licensees, vec![WALLET_ADDRESS.to_string()]);
+        assert_eq!(outcome.status, LicenseeBatchStatus::Failed);
+        assert!(outcome.tx_hash.is_none());
+        assert!(outcome.error.is_some());
+    }
+}
@@ -1,12 +1,12 @@
 use ethers::utils::keccak256;
 
-use crate::utils::LICENSE;
+use crate::utils::{LICENSE, CustomError};
 
 /// Fetches a license permit's base terms URL from `Permit.sol - getLicense`.
 /// 
 /// If the permit does not exist, this function will return an empty string.
-pub async fn get_license_base_terms(permit: String) -> String {
+pub async fn get_license_base_terms(permit: String) -> Result<String, CustomError> {
     let license_hash = keccak256(permit);
 
-    LICENSE.get_license(license_hash).await.unwrap()
+    Ok(LICENSE.get_license(license_hash).await?)
 }
\ No newline at end of file
@@ -1,5 +1,9 @@
 pub mod licensing;
 pub mod user;
+pub mod auth;
+pub mod kyc;
 
 pub use licensing::*;
-pub use user::*;
\ No newline at end of file
+pub use user::*;
+pub use auth::*;
+pub use kyc::*;
\ No newline at end of file
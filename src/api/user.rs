@@ -1,56 +1,231 @@
-use chrono::{DateTime, Utc};
-use mongodb::{Collection, bson::{oid::ObjectId, doc}};
-use serde_json::Value;
+use chrono::Utc;
+use mongodb::bson::oid::ObjectId;
+use serde_json::{json, Value};
 
-use crate::{models::{ApiResponse, User, Session}, utils::CustomError, configs::get_collection};
+use crate::{
+    models::{User, Session, CreateUser, UpdateUser, ListUsersQuery, Pagination, Tombstone, Licensee},
+    utils::{CustomError, is_valid_email, is_valid_e164_phone},
+    repositories::{UserRepository, SessionRepository, NonceRepository, KycRepository, TombstoneRepository, LicenseeRecordRepository, UserUpdate, UserFilter, UserSortField}
+};
+
+use super::verify_siwe_login;
 
 /// Creates a user and simultaneously a new session instance, storing both to the database.
 /// 
-/// Returns the Object ID of both the newly created user and the session instance if no errors occur.
+/// The SIWE (EIP-4361) message is rebuilt from the payload and its signature is verified before anything is stored.
+/// 
+/// Returns the Object ID of the newly created user, the stored session instance and its bearer token if no errors occur.
 /// 
 /// NOTE: We assume that a new user will obviously not have an existing session instance, so we don't check for that here.
-pub async fn create_user(
-    wallet_address: String,
-    expiration_date: i64,
-    chain_id: u32,
-    domain: String,
-    nonce: String,
-    signature: String,
-    payload: Option<Value>,
-    profile_id: String,
-    uri: String,
-    version: u8
-) -> Result<Vec<ObjectId>, CustomError> {
+pub async fn create_user<N, U, S>(nonces: &N, users: &U, sessions: &S, payload: CreateUser) -> Result<(ObjectId, Session, String), CustomError>
+where
+    N: NonceRepository + ?Sized,
+    U: UserRepository + ?Sized,
+    S: SessionRepository + ?Sized,
+{
+    let expiration_date = verify_siwe_login(nonces, &payload).await?;
+
     // checks if the user exists. if they do, return an error.
-    if check_user_exists(wallet_address.clone()).await? {
-        return Err(CustomError::DatabaseError("To be created user already exists.".to_string()));
+    if check_user_exists(users, payload.wallet_address.clone()).await? {
+        return Err(CustomError::Conflict("To be created user already exists.".to_string()));
     }
 
-    let user = User::new(wallet_address.clone());
-    let user_id = user.store_user().await?;
-
-    let session = Session::new(
-        expiration_date,
-        wallet_address,
-        chain_id,
-        domain,
-        nonce,
-        signature,
-        payload,
-        profile_id,
-        uri,
-        version
-    );
+    let user = User::new(payload.wallet_address.clone());
+    let user_id = users.store_user(&user).await?;
 
-    let session_id = session.store_session().await?;
+    let (session, token) = Session::new(payload, expiration_date);
+    sessions.store_session(&session).await?;
 
-    Ok(Vec::from([user_id, session_id]))
+    Ok((user_id, session, token))
 }
 
 /// Checks whether a user with the specified wallet address exists.
-pub async fn check_user_exists(wallet_address: String) -> Result<bool, CustomError> {
-    let user_col: Collection<User> = get_collection("MainDatabase", "Users").await;
-    let user = user_col.find_one(doc! { "wallet_address": wallet_address }, None).await?;
+pub async fn check_user_exists<U: UserRepository + ?Sized>(users: &U, wallet_address: String) -> Result<bool, CustomError> {
+    users.user_exists(&wallet_address).await
+}
+
+/// Updates the web2-related fields of a user's profile.
+/// 
+/// Each provided field is validated first; fields that aren't provided are left untouched.
+/// Text fields can't be cleared: an empty (or whitespace-only) `name`, `address` or `company` is rejected.
+/// Returns the updated user.
+pub async fn update_user<U: UserRepository + ?Sized>(users: &U, wallet_address: String, update: UpdateUser) -> Result<User, CustomError> {
+    let mut fields = UserUpdate::default();
+
+    if let Some(name) = update.name {
+        if name.trim().is_empty() {
+            return Err(CustomError::Validation("name cannot be empty.".to_string()));
+        }
+
+        fields.name = Some(name.trim().to_string());
+    }
+
+    if let Some(dob) = update.dob {
+        let dob = dob.to_timestamp("dob")?;
+
+        if dob > Utc::now().timestamp() {
+            return Err(CustomError::Validation("dob cannot be in the future.".to_string()));
+        }
+
+        fields.dob = Some(dob);
+    }
+
+    if let Some(email) = update.email {
+        if !is_valid_email(&email) {
+            return Err(CustomError::Validation("email is not a valid email address.".to_string()));
+        }
+
+        fields.email = Some(email.to_lowercase());
+    }
+
+    if let Some(phone) = update.phone {
+        if !is_valid_e164_phone(&phone) {
+            return Err(CustomError::Validation("phone is not E.164 compliant.".to_string()));
+        }
+
+        fields.phone = Some(phone);
+    }
+
+    if let Some(address) = update.address {
+        if address.trim().is_empty() {
+            return Err(CustomError::Validation("address cannot be empty.".to_string()));
+        }
+
+        fields.address = Some(address.trim().to_string());
+    }
+
+    if let Some(company) = update.company {
+        if company.trim().is_empty() {
+            return Err(CustomError::Validation("company cannot be empty.".to_string()));
+        }
+
+        fields.company = Some(company.trim().to_string());
+    }
 
-    Ok(user.is_some())
-}
\ No newline at end of file
+    if fields.is_empty() {
+        return Err(CustomError::Validation("No fields to update.".to_string()));
+    }
+
+    users.update_user(&wallet_address, fields).await
+}
+
+/// Lists users for admins, paginated and filtered according to `query`.
+/// 
+/// Returns the users on the requested page along with the pagination data.
+pub async fn list_users<U: UserRepository + ?Sized>(users: &U, query: ListUsersQuery) -> Result<(Vec<User>, Pagination), CustomError> {
+    let page = query.page.unwrap_or(1).max(1);
+    let page_size = query.page_size.unwrap_or(20).clamp(1, 100);
+
+    // a large enough `page` would overflow the amount of users to skip.
+    let skip = (page - 1).checked_mul(page_size)
+        .and_then(|skip| u64::try_from(skip).ok())
+        .ok_or(CustomError::Validation(format!("page {} is out of range.", page)))?;
+
+    let sort_by = match query.sort_by.as_deref() {
+        None | Some("created_at") => UserSortField::CreatedAt,
+        Some("updated_at") => UserSortField::UpdatedAt,
+        Some(field) => return Err(CustomError::Validation(format!("Cannot sort by {}.", field)))
+    };
+
+    let ascending = match query.sort_order.as_deref() {
+        None | Some("desc") => false,
+        Some("asc") => true,
+        Some(order) => return Err(CustomError::Validation(format!("Invalid sort order {}.", order)))
+    };
+
+    let (users, total_items) = users.list_users(UserFilter {
+        kyc_verified: query.kyc_verified,
+        company: query.company
+            .map(|company| company.trim().to_string())
+            .filter(|company| !company.is_empty()),
+        created_from: query.created_from,
+        created_to: query.created_to,
+        sort_by,
+        ascending,
+        skip,
+        limit: page_size as i64
+    }).await?;
+
+    Ok((users, Pagination {
+        total_items: total_items as usize,
+        page,
+        page_size
+    }))
+}
+
+/// Deletes a user's account along with all of their sessions, KYC submissions and off-chain licensee records, leaving a tombstone behind.
+/// 
+/// NOTE: if `AUTH_MODE` is "token", already issued access tokens remain valid until they expire.
+pub async fn delete_user<U, S, K, R, T>(
+    users: &U,
+    sessions: &S,
+    kyc_submissions: &K,
+    licensee_records: &R,
+    tombstones: &T,
+    wallet_address: String
+) -> Result<(), CustomError>
+where
+    U: UserRepository + ?Sized,
+    S: SessionRepository + ?Sized,
+    K: KycRepository + ?Sized,
+    R: LicenseeRecordRepository + ?Sized,
+    T: TombstoneRepository + ?Sized,
+{
+    if !check_user_exists(users, wallet_address.clone()).await? {
+        return Err(CustomError::NotFound("User not found.".to_string()));
+    }
+
+    sessions.revoke_all_sessions(&wallet_address).await?;
+    kyc_submissions.delete_submissions(&wallet_address).await?;
+    licensee_records.delete_records(&wallet_address).await?;
+    users.delete_user(&wallet_address).await?;
+    tombstones.store_tombstone(&Tombstone::new(&wallet_address)).await?;
+
+    Ok(())
+}
+
+/// Exports everything we store about a user's wallet as a single JSON bundle.
+/// 
+/// This includes their profile, sessions (without their bearer tokens), KYC submissions and on-chain licensee data (if any).
+pub async fn export_user_data<U, S, K, R>(
+    users: &U,
+    sessions: &S,
+    kyc_submissions: &K,
+    licensee_records: &R,
+    wallet_address: String
+) -> Result<Value, CustomError>
+where
+    U: UserRepository + ?Sized,
+    S: SessionRepository + ?Sized,
+    K: KycRepository + ?Sized,
+    R: LicenseeRecordRepository + ?Sized,
+{
+    let user = users.get_user(&wallet_address).await?;
+    let kyc_submissions = kyc_submissions.get_submissions(&wallet_address).await?;
+
+    // bearer token hashes are credentials rather than personal data, so they are left out of the export.
+    let sessions: Vec<Value> = sessions.get_sessions(&wallet_address).await?
+        .into_iter()
+        .map(|session| {
+            let mut session = json!(session);
+
+            if let Some(session) = session.as_object_mut() {
+                session.remove("token_hash");
+            }
+
+            session
+        })
+        .collect();
+
+    // the licensee account lives on-chain, so failing to fetch it shouldn't fail the whole export.
+    let licensee = Licensee::get_account_data(licensee_records, wallet_address.clone()).await.ok().flatten();
+
+    Ok(json!({
+        "walletAddress": wallet_address.to_lowercase(),
+        "exportedAt": Utc::now().timestamp(),
+        "profile": user,
+        "sessions": sessions,
+        "kycSubmissions": kyc_submissions,
+        "licensee": licensee
+    }))
+}
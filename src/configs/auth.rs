@@ -0,0 +1,63 @@
+use std::env;
+
+use lazy_static::lazy_static;
+
+/// `AuthMode` determines how authenticated requests are verified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthMode {
+    /// every request's bearer token is looked up in the `Sessions` collection
+    Session,
+    /// requests carry a short-lived signed access token; the `Sessions` collection is only checked on refresh
+    Token,
+}
+
+/// how long (in seconds) a signed access token remains valid for
+pub const ACCESS_TOKEN_TTL: i64 = 900;
+
+/// the longest (in seconds) a session remains valid for, regardless of the expiration time the user signed
+pub const MAX_SESSION_TTL: i64 = 7 * 24 * 60 * 60;
+
+/// how far (in seconds) a SIWE message's issuance time may be ahead of the server's clock, to tolerate clock drift
+pub const MAX_SIWE_CLOCK_SKEW: i64 = 60;
+
+/// the minimum length (in bytes) of `ACCESS_TOKEN_SECRET`, i.e. the output size of HMAC-SHA256
+pub const MIN_ACCESS_TOKEN_SECRET_LEN: usize = 32;
+
+lazy_static! {
+    // reads the auth mode from `AUTH_MODE` ("session" or "token"), defaulting to "session".
+    pub static ref AUTH_MODE: AuthMode = {
+        match env::var("AUTH_MODE").unwrap_or_default().to_lowercase().as_str() {
+            "token" => AuthMode::Token,
+            "" | "session" => AuthMode::Session,
+            mode => panic!("Invalid AUTH_MODE given: {}", mode)
+        }
+    };
+
+    // the domains that are allowed to request a login, read from `SIWE_ALLOWED_DOMAINS` (comma-separated).
+    pub static ref SIWE_ALLOWED_DOMAINS: Vec<String> = {
+        let domains: Vec<String> = env::var("SIWE_ALLOWED_DOMAINS")
+            .expect("SIWE_ALLOWED_DOMAINS must be set")
+            .split(',')
+            .map(|domain| domain.trim().to_string())
+            .filter(|domain| !domain.is_empty())
+            .collect();
+
+        assert!(!domains.is_empty(), "SIWE_ALLOWED_DOMAINS must contain at least one domain");
+
+        domains
+    };
+
+    // the secret key used to sign access tokens, which must be at least `MIN_ACCESS_TOKEN_SECRET_LEN` bytes long.
+    // only required when `AUTH_MODE` is "token".
+    pub static ref ACCESS_TOKEN_SECRET: Vec<u8> = {
+        let secret = env::var("ACCESS_TOKEN_SECRET").expect("ACCESS_TOKEN_SECRET must be set").into_bytes();
+
+        assert!(
+            secret.len() >= MIN_ACCESS_TOKEN_SECRET_LEN,
+            "ACCESS_TOKEN_SECRET must be at least {} bytes long",
+            MIN_ACCESS_TOKEN_SECRET_LEN
+        );
+
+        secret
+    };
+}
@@ -0,0 +1,146 @@
+use std::env;
+
+use lazy_static::lazy_static;
+use log::info;
+use mongodb::{bson::{doc, Document}, options::{CreateCollectionOptions, ValidationLevel}, Database};
+
+use crate::{repositories::{MongoUserRepository, MongoSessionRepository, MongoNonceRepository, MongoKycRepository, MongoLicenseeRecordRepository}, utils::CustomError};
+
+lazy_static! {
+    // whether JSON-schema validators are applied to the collections at startup, read from `MONGO_SCHEMA_VALIDATION` (defaults to false).
+    pub static ref MONGO_SCHEMA_VALIDATION: bool = {
+        env::var("MONGO_SCHEMA_VALIDATION")
+            .map(|enabled| enabled.parse::<bool>().expect("Invalid MONGO_SCHEMA_VALIDATION given"))
+            .unwrap_or(false)
+    };
+
+    // whether pending migrations are applied automatically at startup, read from `MIGRATE_ON_STARTUP` (defaults to true).
+    pub static ref MIGRATE_ON_STARTUP: bool = {
+        env::var("MIGRATE_ON_STARTUP")
+            .map(|enabled| enabled.parse::<bool>().expect("Invalid MIGRATE_ON_STARTUP given"))
+            .unwrap_or(true)
+    };
+}
+
+/// Prepares the database before the server starts accepting requests.
+///
+/// Applies the JSON-schema validators (if `MONGO_SCHEMA_VALIDATION` is enabled) and ensures that all indexes exist.
+/// Every step is idempotent, so this is safe to run on every startup.
+pub async fn bootstrap_db(db: &Database) -> Result<(), CustomError> {
+    if *MONGO_SCHEMA_VALIDATION {
+        let existing = db.list_collection_names(None).await?;
+
+        for (col_name, validator) in schema_validators() {
+            apply_validator(db, &existing, col_name, validator).await?;
+        }
+    }
+
+    MongoUserRepository::new(db).create_indexes().await?;
+    MongoSessionRepository::new(db).create_indexes().await?;
+    MongoNonceRepository::new(db).create_indexes().await?;
+    MongoKycRepository::new(db).create_indexes().await?;
+    MongoLicenseeRecordRepository::new(db).create_indexes().await?;
+
+    info!("Database bootstrapped (schema validation: {})", *MONGO_SCHEMA_VALIDATION);
+
+    Ok(())
+}
+
+/// Applies `validator` to the given collection, creating the collection first if it doesn't exist yet.
+///
+/// NOTE: the "moderate" validation level is used so that documents stored before the validator existed can still be updated.
+async fn apply_validator(db: &Database, existing: &[String], col_name: &str, validator: Document) -> Result<(), CustomError> {
+    if existing.iter().any(|name| name == col_name) {
+        db.run_command(
+            doc! {
+                "collMod": col_name,
+                "validator": validator,
+                "validationLevel": "moderate"
+            },
+            None
+        ).await?;
+    } else {
+        let options = CreateCollectionOptions::builder()
+            .validator(validator)
+            .validation_level(ValidationLevel::Moderate)
+            .build();
+
+        db.create_collection(col_name, options).await?;
+    }
+
+    Ok(())
+}
+
+/// Returns the JSON-schema validators of each collection.
+fn schema_validators() -> Vec<(&'static str, Document)> {
+    // wallet addresses are always stored lowercased.
+    let wallet_address = doc! { "bsonType": "string", "pattern": "^0x[0-9a-f]{40}$" };
+    let timestamp = doc! { "bsonType": ["long", "int"] };
+
+    vec![
+        ("Users", doc! {
+            "$jsonSchema": {
+                "bsonType": "object",
+                "required": ["wallet_address", "created_at", "updated_at", "kyc_verified", "last_kyc_verification"],
+                "properties": {
+                    "wallet_address": wallet_address.clone(),
+                    "created_at": timestamp.clone(),
+                    "updated_at": timestamp.clone(),
+                    "dob": timestamp.clone(),
+                    "email": { "bsonType": "string" },
+                    "phone": { "bsonType": "string" },
+                    "kyc_verified": { "bsonType": "bool" },
+                    "last_kyc_verification": timestamp.clone()
+                }
+            }
+        }),
+        ("Sessions", doc! {
+            "$jsonSchema": {
+                "bsonType": "object",
+                "required": ["wallet_address", "expiration_date", "chain_id", "domain", "nonce", "signature"],
+                "properties": {
+                    "wallet_address": wallet_address.clone(),
+                    "expiration_date": timestamp.clone(),
+                    "expires_at": { "bsonType": "date" },
+                    "token_hash": { "bsonType": "string" }
+                }
+            }
+        }),
+        ("Nonces", doc! {
+            "$jsonSchema": {
+                "bsonType": "object",
+                "required": ["nonce", "created_at", "expires_at"],
+                "properties": {
+                    "nonce": { "bsonType": "string" },
+                    "created_at": timestamp.clone(),
+                    "expires_at": { "bsonType": "date" }
+                }
+            }
+        }),
+        ("KycSubmissions", doc! {
+            "$jsonSchema": {
+                "bsonType": "object",
+                "required": ["wallet_address", "documents", "status", "submitted_at"],
+                "properties": {
+                    "wallet_address": wallet_address.clone(),
+                    "documents": { "bsonType": "array", "minItems": 1 },
+                    "status": { "enum": ["pending", "approved", "rejected"] },
+                    "submitted_at": timestamp.clone()
+                }
+            }
+        }),
+        ("LicenseeRecords", doc! {
+            "$jsonSchema": {
+                "bsonType": "object",
+                "required": ["wallet_address", "licensee", "salt", "created_at"],
+                "properties": {
+                    "wallet_address": wallet_address,
+                    "licensee": { "bsonType": "object" },
+                    "salt": { "bsonType": "string", "pattern": "^[0-9a-f]{64}$" },
+                    "created_at": timestamp,
+                    "pending": { "bsonType": "bool" }
+                }
+            }
+        }),
+    ]
+}
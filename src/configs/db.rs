@@ -1,7 +1,69 @@
 use mongodb::{Client, options::{ClientOptions, ResolverConfig, ServerApiVersion, ServerApi}, Database, Collection};
-use std::env;
+use std::{env, sync::Arc};
+
+use crate::repositories::{
+    UserRepository, SessionRepository, NonceRepository, KycRepository, TombstoneRepository, LicenseeRecordRepository,
+    MongoUserRepository, MongoSessionRepository, MongoNonceRepository, MongoKycRepository, MongoTombstoneRepository, MongoLicenseeRecordRepository
+};
+
+/// the name of the MongoDB database used by the API
+pub const DB_NAME: &str = "MainDatabase";
+
+/// `AppState` holds the state shared by all route handlers.
+/// 
+/// It is created once at startup and injected into handlers via axum's `State` extractor.
+/// Handlers only ever reach storage through these repositories, never through the database directly.
+#[derive(Clone)]
+pub struct AppState {
+    /// where `User` instances are stored
+    pub users: Arc<dyn UserRepository>,
+    /// where `Session` instances are stored
+    pub sessions: Arc<dyn SessionRepository>,
+    /// where the nonces issued for the SIWE login flow are stored
+    pub nonces: Arc<dyn NonceRepository>,
+    /// where `KycSubmission` instances are stored
+    pub kyc_submissions: Arc<dyn KycRepository>,
+    /// where the `Tombstone` instances of deleted users are stored
+    pub tombstones: Arc<dyn TombstoneRepository>,
+    /// where off-chain `LicenseeRecord` instances are stored
+    pub licensee_records: Arc<dyn LicenseeRecordRepository>,
+}
+
+impl AppState {
+    /// Creates a new `AppState` instance whose repositories are all backed by the given MongoDB database.
+    pub fn new(db: &Database) -> Self {
+        Self {
+            users: Arc::new(MongoUserRepository::new(db)),
+            sessions: Arc::new(MongoSessionRepository::new(db)),
+            nonces: Arc::new(MongoNonceRepository::new(db)),
+            kyc_submissions: Arc::new(MongoKycRepository::new(db)),
+            tombstones: Arc::new(MongoTombstoneRepository::new(db)),
+            licensee_records: Arc::new(MongoLicenseeRecordRepository::new(db)),
+        }
+    }
+
+    /// Creates a new `AppState` instance whose repositories are all kept in memory, so that handlers can be tested without MongoDB.
+    #[cfg(test)]
+    pub fn in_memory() -> Self {
+        use crate::repositories::{
+            InMemoryUserRepository, InMemorySessionRepository, InMemoryNonceRepository,
+            InMemoryKycRepository, InMemoryTombstoneRepository, InMemoryLicenseeRecordRepository
+        };
+
+        Self {
+            users: Arc::new(InMemoryUserRepository::new()),
+            sessions: Arc::new(InMemorySessionRepository::new()),
+            nonces: Arc::new(InMemoryNonceRepository::new()),
+            kyc_submissions: Arc::new(InMemoryKycRepository::new()),
+            tombstones: Arc::new(InMemoryTombstoneRepository::new()),
+            licensee_records: Arc::new(InMemoryLicenseeRecordRepository::new()),
+        }
+    }
+}
 
 /// connects to MongoDB and returns its client instance
+/// 
+/// NOTE: this should only be called once at startup; the client is cheap to clone and pools its connections.
 pub async fn connect_mongo() -> Client {
     let mongo_uri = env::var("MONGODB_URI").expect("MONGODB_URI must be set");
 
@@ -10,22 +72,12 @@ pub async fn connect_mongo() -> Client {
     let server_api = ServerApi::builder().version(ServerApiVersion::V1).build();
     client_options.server_api = Some(server_api);
 
-    let client = Client::with_options(client_options).unwrap_or_else(|err| {
+    Client::with_options(client_options).unwrap_or_else(|err| {
         panic!("Failed to initialize MongoDB client: {}", err);
-    });
-
-    client
+    })
 }
 
-/// returns a MongoDB database instance from the given `name`.
-pub async fn get_db(name: &str) -> Database {
-    let client = connect_mongo().await;
-    client.database(name)
-}
-
-/// returns a MongoDB collection instance from the given `db_name` and `col_name`.
-pub async fn get_collection<T>(db_name: &str, col_name: &str) -> Collection<T> {
-    let db = get_db(db_name).await;
-    
+/// returns a MongoDB collection instance from the given `db` and `col_name`.
+pub fn get_collection<T>(db: &Database, col_name: &str) -> Collection<T> {
     db.collection::<T>(col_name)
-}
\ No newline at end of file
+}
@@ -0,0 +1,12 @@
+use std::env;
+
+use lazy_static::lazy_static;
+
+lazy_static! {
+    // the amount of days a KYC verification remains valid for, read from `KYC_EXPIRY_DAYS` (defaults to 365).
+    pub static ref KYC_EXPIRY_DAYS: i64 = {
+        env::var("KYC_EXPIRY_DAYS")
+            .map(|days| days.parse::<i64>().expect("Invalid KYC_EXPIRY_DAYS given"))
+            .unwrap_or(365)
+    };
+}
@@ -0,0 +1,116 @@
+use std::{collections::HashMap, env, time::Duration};
+
+use ethers::utils::hex::decode;
+use lazy_static::lazy_static;
+
+/// `LicenseeStorageMode` determines where a licensee's account information is stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LicenseeStorageMode {
+    /// the account information is stored in `Licensee.sol` (encrypted if `LICENSEE_ENCRYPTION_KEY` is set)
+    OnChain,
+    /// the account information is stored in the database; only a salted commitment to it is stored in `Licensee.sol`
+    Commitment,
+}
+
+/// `LicenseeKeyring` holds the server keys that wrap licensee data keys (see `encrypt_licensee_data`), each identified by a one-byte key ID.
+///
+/// The ID is stored alongside the encrypted data, so that the current key can be rotated while data wrapped with a retired key remains readable.
+#[derive(Debug, Clone)]
+pub struct LicenseeKeyring {
+    /// the ID of the key that new data is wrapped with
+    current_id: u8,
+    /// every known key, keyed by its ID
+    keys: HashMap<u8, [u8; 32]>,
+}
+
+impl LicenseeKeyring {
+    /// Creates a new `LicenseeKeyring` instance from the current key and the keys it replaced.
+    ///
+    /// Panics if a retired key reuses the current key's ID, since data wrapped with it would become unreadable.
+    pub fn new(current_id: u8, current_key: [u8; 32], retired: HashMap<u8, [u8; 32]>) -> Self {
+        assert!(!retired.contains_key(&current_id), "Retired licensee encryption key {} reuses the current key's ID", current_id);
+
+        let mut keys = retired;
+        keys.insert(current_id, current_key);
+
+        Self { current_id, keys }
+    }
+
+    /// Returns the ID of the current key along with the key itself.
+    pub fn current(&self) -> (u8, &[u8; 32]) {
+        (self.current_id, &self.keys[&self.current_id])
+    }
+
+    /// Gets the key with the given ID, if it is known.
+    pub fn get(&self, key_id: u8) -> Option<&[u8; 32]> {
+        self.keys.get(&key_id)
+    }
+}
+
+/// Parses a 32-byte, hex-encoded encryption key.
+fn parse_encryption_key(key: &str) -> Option<[u8; 32]> {
+    decode(key.trim()).ok().and_then(|key| key.try_into().ok())
+}
+
+lazy_static! {
+    // the server keys used to wrap each licensee's data key, or `None` if licensee data is written on-chain unencrypted.
+    // the current key is read from `LICENSEE_ENCRYPTION_KEY` (32 hex-encoded bytes) and its ID from `LICENSEE_ENCRYPTION_KEY_ID` (0-255, defaults to 0).
+    // keys retired by a rotation are read from `LICENSEE_RETIRED_ENCRYPTION_KEYS` (comma-separated `<id>:<hex key>` pairs),
+    // and are only used to decrypt data that was written before the rotation.
+    pub static ref LICENSEE_ENCRYPTION_KEYS: Option<LicenseeKeyring> = {
+        env::var("LICENSEE_ENCRYPTION_KEY").ok().map(|key| {
+            let current_id = env::var("LICENSEE_ENCRYPTION_KEY_ID")
+                .map(|id| id.trim().parse::<u8>().expect("LICENSEE_ENCRYPTION_KEY_ID must be between 0 and 255"))
+                .unwrap_or(0);
+
+            let retired = env::var("LICENSEE_RETIRED_ENCRYPTION_KEYS")
+                .unwrap_or_default()
+                .split(',')
+                .filter(|entry| !entry.trim().is_empty())
+                .map(|entry| {
+                    let (id, key) = entry.split_once(':').expect("LICENSEE_RETIRED_ENCRYPTION_KEYS entries must be `<id>:<hex key>` pairs");
+                    let id = id.trim().parse::<u8>().expect("LICENSEE_RETIRED_ENCRYPTION_KEYS IDs must be between 0 and 255");
+
+                    (id, parse_encryption_key(key).expect("LICENSEE_RETIRED_ENCRYPTION_KEYS keys must be 32 hex-encoded bytes"))
+                })
+                .collect();
+
+            let key = parse_encryption_key(&key).expect("LICENSEE_ENCRYPTION_KEY must be 32 hex-encoded bytes");
+
+            LicenseeKeyring::new(current_id, key, retired)
+        })
+    };
+
+    // where licensee account information is stored, read from `LICENSEE_STORAGE_MODE` (defaults to on-chain).
+    pub static ref LICENSEE_STORAGE_MODE: LicenseeStorageMode = {
+        match env::var("LICENSEE_STORAGE_MODE").as_deref() {
+            Ok("onchain") | Err(_) => LicenseeStorageMode::OnChain,
+            Ok("commitment") => LicenseeStorageMode::Commitment,
+            Ok(mode) => panic!("Invalid LICENSEE_STORAGE_MODE given: {}", mode)
+        }
+    };
+
+    // the minimum age (in years) a user must be to register as a licensee, read from `LICENSEE_MIN_AGE` (defaults to 18).
+    pub static ref LICENSEE_MIN_AGE: u32 = {
+        env::var("LICENSEE_MIN_AGE")
+            .map(|age| age.parse::<u32>().expect("Invalid LICENSEE_MIN_AGE given"))
+            .unwrap_or(18)
+    };
+
+    // the maximum amount of licensees included in a single batch transaction, read from `LICENSEE_BATCH_SIZE` (defaults to 50).
+    // larger requests are split into multiple transactions to stay under the block gas limit.
+    pub static ref LICENSEE_BATCH_SIZE: usize = {
+        env::var("LICENSEE_BATCH_SIZE")
+            .map(|size| size.parse::<usize>().ok().filter(|size| *size > 0).expect("Invalid LICENSEE_BATCH_SIZE given"))
+            .unwrap_or(50)
+    };
+
+    // how long a batch request waits (in total) for its transactions to be mined, read from `LICENSEE_RECEIPT_TIMEOUT_SECS` (defaults to 20).
+    // the wait happens inside the HTTP request, so it must stay below the timeouts of any proxy in front of the API;
+    // batches that aren't mined in time are reported as submitted, and 0 returns every transaction hash as soon as it is sent.
+    pub static ref LICENSEE_RECEIPT_TIMEOUT: Duration = {
+        env::var("LICENSEE_RECEIPT_TIMEOUT_SECS")
+            .map(|secs| Duration::from_secs(secs.parse::<u64>().expect("Invalid LICENSEE_RECEIPT_TIMEOUT_SECS given")))
+            .unwrap_or(Duration::from_secs(20))
+    };
+}
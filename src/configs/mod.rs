@@ -1,5 +1,13 @@
 pub mod env;
 pub mod db;
+pub mod auth;
+pub mod kyc;
+pub mod bootstrap;
+pub mod licensee;
 
 pub use env::*;
-pub use db::*;
\ No newline at end of file
+pub use db::*;
+pub use auth::*;
+pub use kyc::*;
+pub use bootstrap::*;
+pub use licensee::*;
\ No newline at end of file
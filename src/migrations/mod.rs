@@ -0,0 +1,110 @@
+pub mod steps;
+
+pub use steps::*;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use log::info;
+use mongodb::{bson::doc, Collection, Database};
+use serde::{Deserialize, Serialize};
+
+use crate::{configs::get_collection, utils::CustomError};
+
+/// `Migration` is a single, ordered step that brings stored documents up to date with the current models.
+///
+/// Steps must be idempotent: running one against documents it has already migrated should be a no-op.
+#[async_trait]
+pub trait Migration: Send + Sync {
+    /// the unique, sortable ID of the migration (e.g. "0001_user_kyc_fields")
+    fn id(&self) -> &'static str;
+
+    /// a short, human-readable description of what the migration does
+    fn description(&self) -> &'static str;
+
+    /// Counts the documents that still need to be migrated, without changing anything.
+    async fn pending(&self, db: &Database) -> Result<u64, CustomError>;
+
+    /// Migrates the documents, returning the amount of documents changed.
+    async fn apply(&self, db: &Database) -> Result<u64, CustomError>;
+}
+
+/// `MigrationRecord` struct that represents an applied migration in the `_migrations` collection.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MigrationRecord {
+    /// the ID of the applied migration
+    pub _id: String,
+    /// the description of the applied migration
+    pub description: String,
+    /// the amount of documents changed when the migration was applied
+    pub changed: u64,
+    /// when the migration was applied (UNIX format)
+    pub applied_at: i64,
+}
+
+/// `MigrationReport` describes what happened (or would happen, in dry-run mode) for a single migration.
+#[derive(Debug)]
+pub struct MigrationReport {
+    /// the ID of the migration
+    pub id: &'static str,
+    /// whether the migration had already been applied before this run
+    pub already_applied: bool,
+    /// the amount of documents changed (or, in dry-run mode, that would be changed)
+    pub changed: u64,
+}
+
+/// Returns all migrations, in the order that they must be applied.
+///
+/// NOTE: new migrations must only ever be appended; applied IDs are stored and never re-run.
+pub fn migrations() -> Vec<Box<dyn Migration>> {
+    vec![
+        Box::new(UserKycFields),
+        Box::new(LowercaseWalletAddresses),
+        Box::new(SessionExpiresAt),
+        Box::new(HashSessionTokens),
+    ]
+}
+
+/// Runs all migrations that haven't been applied yet, in order, recording each one in the `_migrations` collection.
+///
+/// In dry-run mode, nothing is written; the reports contain the amount of documents each pending migration would change.
+pub async fn run_migrations(db: &Database, dry_run: bool) -> Result<Vec<MigrationReport>, CustomError> {
+    let migration_col: Collection<MigrationRecord> = get_collection(db, "_migrations");
+    let mut reports = Vec::new();
+
+    for migration in migrations() {
+        let already_applied = migration_col.find_one(doc! { "_id": migration.id() }, None).await?.is_some();
+
+        if already_applied {
+            reports.push(MigrationReport { id: migration.id(), already_applied, changed: 0 });
+            continue;
+        }
+
+        if dry_run {
+            let pending = migration.pending(db).await?;
+            info!("[dry run] Migration {} ({}) would change {} document(s)", migration.id(), migration.description(), pending);
+
+            reports.push(MigrationReport { id: migration.id(), already_applied, changed: pending });
+            continue;
+        }
+
+        let changed = migration.apply(db).await?;
+        info!("Applied migration {} ({}), changing {} document(s)", migration.id(), migration.description(), changed);
+
+        let record = MigrationRecord {
+            _id: migration.id().to_string(),
+            description: migration.description().to_string(),
+            changed,
+            applied_at: Utc::now().timestamp(),
+        };
+
+        // another instance may have applied (and recorded) the same migration concurrently, which is fine since steps are idempotent.
+        match migration_col.insert_one(record, None).await.map_err(CustomError::from) {
+            Ok(_) | Err(CustomError::Conflict(_)) => (),
+            Err(e) => return Err(e)
+        }
+
+        reports.push(MigrationReport { id: migration.id(), already_applied, changed });
+    }
+
+    Ok(reports)
+}
@@ -0,0 +1,208 @@
+use async_trait::async_trait;
+use futures::stream::TryStreamExt;
+use mongodb::{bson::{doc, Document}, Collection, Database};
+
+use crate::{configs::get_collection, models::Session, utils::CustomError};
+
+use super::Migration;
+
+/// Backfills `kyc_verified` and `last_kyc_verification` on users stored before KYC was introduced.
+pub struct UserKycFields;
+
+#[async_trait]
+impl Migration for UserKycFields {
+    fn id(&self) -> &'static str {
+        "0001_user_kyc_fields"
+    }
+
+    fn description(&self) -> &'static str {
+        "backfill KYC fields on users"
+    }
+
+    async fn pending(&self, db: &Database) -> Result<u64, CustomError> {
+        let user_col: Collection<Document> = get_collection(db, "Users");
+
+        Ok(user_col.count_documents(missing_kyc_fields(), None).await?)
+    }
+
+    async fn apply(&self, db: &Database) -> Result<u64, CustomError> {
+        let user_col: Collection<Document> = get_collection(db, "Users");
+
+        let kyc_verified = user_col.update_many(
+            doc! { "kyc_verified": { "$exists": false } },
+            doc! { "$set": { "kyc_verified": false } },
+            None
+        ).await?;
+        let last_kyc_verification = user_col.update_many(
+            doc! { "last_kyc_verification": { "$exists": false } },
+            doc! { "$set": { "last_kyc_verification": 0_i64 } },
+            None
+        ).await?;
+
+        Ok(kyc_verified.modified_count.max(last_kyc_verification.modified_count))
+    }
+}
+
+/// the filter matching users that are missing any of the KYC fields
+fn missing_kyc_fields() -> Document {
+    doc! {
+        "$or": [
+            { "kyc_verified": { "$exists": false } },
+            { "last_kyc_verification": { "$exists": false } }
+        ]
+    }
+}
+
+/// Lowercases the wallet addresses of users and sessions stored before addresses were normalized.
+///
+/// NOTE: this must run before the unique `wallet_address` index is created, as lowercasing may reveal duplicate users.
+/// Such users can't be merged automatically (their profiles and KYC state may differ), so the migration fails, listing them, until they are resolved.
+pub struct LowercaseWalletAddresses;
+
+/// the collections whose `wallet_address` fields are lowercased
+const WALLET_ADDRESS_COLLECTIONS: [&str; 3] = ["Users", "Sessions", "KycSubmissions"];
+
+#[async_trait]
+impl Migration for LowercaseWalletAddresses {
+    fn id(&self) -> &'static str {
+        "0002_lowercase_wallet_addresses"
+    }
+
+    fn description(&self) -> &'static str {
+        "lowercase wallet addresses"
+    }
+
+    async fn pending(&self, db: &Database) -> Result<u64, CustomError> {
+        check_case_duplicate_users(db).await?;
+
+        let mut pending = 0;
+
+        for col_name in WALLET_ADDRESS_COLLECTIONS {
+            let col: Collection<Document> = get_collection(db, col_name);
+            pending += col.count_documents(doc! { "wallet_address": { "$regex": "[A-F]" } }, None).await?;
+        }
+
+        Ok(pending)
+    }
+
+    async fn apply(&self, db: &Database) -> Result<u64, CustomError> {
+        check_case_duplicate_users(db).await?;
+
+        let mut changed = 0;
+
+        for col_name in WALLET_ADDRESS_COLLECTIONS {
+            let col: Collection<Document> = get_collection(db, col_name);
+            let result = col.update_many(
+                doc! { "wallet_address": { "$regex": "[A-F]" } },
+                vec![doc! { "$set": { "wallet_address": { "$toLower": "$wallet_address" } } }],
+                None
+            ).await?;
+
+            changed += result.modified_count;
+        }
+
+        Ok(changed)
+    }
+}
+
+/// Returns an error listing the users whose wallet addresses only differ in case, if there are any.
+async fn check_case_duplicate_users(db: &Database) -> Result<(), CustomError> {
+    let user_col: Collection<Document> = get_collection(db, "Users");
+    let pipeline = vec![
+        doc! { "$group": { "_id": { "$toLower": "$wallet_address" }, "count": { "$sum": 1 } } },
+        doc! { "$match": { "count": { "$gt": 1 } } }
+    ];
+
+    let duplicates: Vec<Document> = user_col.aggregate(pipeline, None).await?.try_collect().await?;
+
+    if duplicates.is_empty() {
+        return Ok(());
+    }
+
+    let wallet_addresses: Vec<&str> = duplicates.iter()
+        .filter_map(|duplicate| duplicate.get_str("_id").ok())
+        .collect();
+
+    Err(CustomError::Conflict(format!(
+        "{} wallet address(es) belong to more than one user once lowercased: {}. Merge or delete the duplicate users, then run the migration again.",
+        wallet_addresses.len(),
+        wallet_addresses.join(", ")
+    )))
+}
+
+/// Backfills `expires_at` on sessions stored before the TTL index was introduced, so that they are eventually purged.
+pub struct SessionExpiresAt;
+
+#[async_trait]
+impl Migration for SessionExpiresAt {
+    fn id(&self) -> &'static str {
+        "0003_session_expires_at"
+    }
+
+    fn description(&self) -> &'static str {
+        "backfill expires_at on sessions"
+    }
+
+    async fn pending(&self, db: &Database) -> Result<u64, CustomError> {
+        let session_col: Collection<Document> = get_collection(db, "Sessions");
+
+        Ok(session_col.count_documents(doc! { "expires_at": { "$exists": false } }, None).await?)
+    }
+
+    async fn apply(&self, db: &Database) -> Result<u64, CustomError> {
+        let session_col: Collection<Document> = get_collection(db, "Sessions");
+
+        // `expiration_date` is stored in UNIX format (seconds), whereas BSON dates are in milliseconds.
+        let result = session_col.update_many(
+            doc! { "expires_at": { "$exists": false } },
+            vec![doc! { "$set": { "expires_at": { "$toDate": { "$multiply": ["$expiration_date", 1000_i64] } } } }],
+            None
+        ).await?;
+
+        Ok(result.modified_count)
+    }
+}
+
+/// Replaces the plaintext bearer tokens of sessions stored before tokens were hashed with their SHA-256 hashes.
+pub struct HashSessionTokens;
+
+#[async_trait]
+impl Migration for HashSessionTokens {
+    fn id(&self) -> &'static str {
+        "0004_hash_session_tokens"
+    }
+
+    fn description(&self) -> &'static str {
+        "replace plaintext session tokens with their hashes"
+    }
+
+    async fn pending(&self, db: &Database) -> Result<u64, CustomError> {
+        let session_col: Collection<Document> = get_collection(db, "Sessions");
+
+        Ok(session_col.count_documents(doc! { "token": { "$exists": true } }, None).await?)
+    }
+
+    async fn apply(&self, db: &Database) -> Result<u64, CustomError> {
+        let session_col: Collection<Document> = get_collection(db, "Sessions");
+        let mut sessions = session_col.find(doc! { "token": { "$exists": true } }, None).await?;
+        let mut changed = 0;
+
+        // MongoDB can't compute SHA-256 hashes itself, so every session is updated individually.
+        while let Some(session) = sessions.try_next().await? {
+            let token_hash = match session.get_str("token") {
+                Ok(token) if !token.is_empty() => Session::hash_token(token),
+                _ => String::new()
+            };
+
+            let result = session_col.update_one(
+                doc! { "_id": session.get("_id") },
+                doc! { "$set": { "token_hash": token_hash }, "$unset": { "token": "" } },
+                None
+            ).await?;
+
+            changed += result.modified_count;
+        }
+
+        Ok(changed)
+    }
+}
@@ -0,0 +1,71 @@
+use chrono::Utc;
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+/// `KycStatus` represents the review status of a KYC submission.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KycStatus {
+    /// the submission is waiting to be reviewed by an owner
+    Pending,
+    /// the submission has been approved
+    Approved,
+    /// the submission has been rejected
+    Rejected,
+}
+
+/// `KycDocument` contains the metadata of a document submitted for KYC.
+/// 
+/// NOTE: the documents themselves are NOT stored here; only enough metadata to locate and verify them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KycDocument {
+    /// the type of document (e.g. "passport", "national_id", "proof_of_address")
+    pub document_type: String,
+    /// the name of the uploaded file
+    pub file_name: String,
+    /// a hash of the file's contents, used to verify that the document hasn't changed
+    pub file_hash: String,
+    /// (optional) where the document is stored
+    pub url: Option<String>,
+}
+
+/// `KycSubmission` struct that represents a user's KYC submission in the database.
+/// 
+/// All dates and timestamps are stored in UNIX format.
+#[skip_serializing_none]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KycSubmission {
+    /// the object ID of the submission in the database
+    pub _id: ObjectId,
+    /// the wallet address of the user that submitted this
+    pub wallet_address: String,
+    /// the metadata of the submitted documents
+    pub documents: Vec<KycDocument>,
+    /// the review status of the submission
+    pub status: KycStatus,
+    /// when the submission was made
+    pub submitted_at: i64,
+    /// when the submission was reviewed
+    pub reviewed_at: Option<i64>,
+    /// the wallet address of the owner that reviewed the submission
+    pub reviewer: Option<String>,
+    /// the reason given by the reviewer (required when rejecting)
+    pub reason: Option<String>,
+}
+
+impl KycSubmission {
+    /// Creates a new, pending `KycSubmission` instance.
+    pub fn new(wallet_address: String, documents: Vec<KycDocument>) -> Self {
+        Self {
+            _id: ObjectId::new(),
+            wallet_address: wallet_address.to_lowercase(),
+            documents,
+            status: KycStatus::Pending,
+            submitted_at: Utc::now().timestamp(),
+            reviewed_at: None,
+            reviewer: None,
+            reason: None,
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use chrono::Utc;
+use ethers::{core::rand::{thread_rng, Rng}, utils::hex::{encode, decode}};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use crate::{models::Licensee, utils::{CustomError, licensee_commitment}};
+
+/// `LicenseeRecord` struct that represents a licensee's account information stored off-chain.
+///
+/// Used when `LICENSEE_STORAGE_MODE` is "commitment": only a salted commitment of the record (and its `ObjectId`) is stored in `Licensee.sol`,
+/// so the record can be deleted while its on-chain anchor remains.
+///
+/// Records built for a user's `registerAccount` transaction are `pending` until their commitment is first read back from `Licensee.sol`;
+/// a user has at most one pending record, which is replaced every time they request new registration parameters.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LicenseeRecord {
+    /// the object ID of the record in the database
+    pub _id: ObjectId,
+    /// the (lowercased) wallet address of the licensee
+    pub wallet_address: String,
+    /// the licensee's account information
+    pub licensee: Licensee,
+    /// the random salt (hex-encoded) that the commitment is computed with
+    pub salt: String,
+    /// when the record was created (UNIX format)
+    pub created_at: i64,
+    /// whether the record's commitment hasn't been seen on-chain yet (records stored before this field existed are confirmed)
+    #[serde(default)]
+    pub pending: bool,
+}
+
+impl LicenseeRecord {
+    /// Creates a new (confirmed) `LicenseeRecord` instance with a random salt.
+    pub fn new(licensee: Licensee) -> Self {
+        Self {
+            _id: ObjectId::new(),
+            wallet_address: licensee.wallet_address.to_lowercase(),
+            licensee,
+            salt: encode(thread_rng().gen::<[u8; 32]>()),
+            created_at: Utc::now().timestamp(),
+            pending: false,
+        }
+    }
+
+    /// Creates a new pending `LicenseeRecord` instance with a random salt.
+    pub fn new_pending(licensee: Licensee) -> Self {
+        Self {
+            pending: true,
+            ..Self::new(licensee)
+        }
+    }
+
+    /// Computes the commitment of this record (see `licensee_commitment`).
+    pub fn commitment(&self) -> Result<[u8; 32], CustomError> {
+        let salt: [u8; 32] = decode(&self.salt)
+            .ok()
+            .and_then(|salt| salt.try_into().ok())
+            .ok_or(CustomError::Database("Licensee record has a malformed salt.".to_string()))?;
+
+        Ok(licensee_commitment(&self.licensee, &salt))
+    }
+}
@@ -1,3 +1,11 @@
 pub mod user;
+pub mod nonce;
+pub mod kyc;
+pub mod tombstone;
+pub mod licensee_record;
 
-pub use user::*;
\ No newline at end of file
+pub use user::*;
+pub use nonce::*;
+pub use kyc::*;
+pub use tombstone::*;
+pub use licensee_record::*;
\ No newline at end of file
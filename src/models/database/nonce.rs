@@ -0,0 +1,44 @@
+use chrono::Utc;
+use ethers::core::rand::{distributions::Alphanumeric, thread_rng, Rng};
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+
+/// how long (in seconds) an issued nonce remains valid for
+pub const NONCE_TTL: i64 = 300;
+
+/// `Nonce` struct that represents a server-issued nonce used in the SIWE login flow.
+///
+/// Each nonce can only back a single session; it is deleted from the database as soon as it is consumed.
+///
+/// NOTE: unlike other models, `expires_at` is stored as a BSON date so that MongoDB's TTL index can purge expired nonces.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Nonce {
+    /// the object ID of the nonce in the database
+    pub _id: ObjectId,
+    /// the random string that the user includes in their SIWE message
+    pub nonce: String,
+    /// when the nonce was issued
+    pub created_at: i64,
+    /// when the nonce expires
+    pub expires_at: DateTime,
+}
+
+impl Nonce {
+    /// Creates a new `Nonce` instance with a random alphanumeric string that expires after `NONCE_TTL` seconds.
+    pub fn generate() -> Self {
+        let nonce: String = thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(17)
+            .map(char::from)
+            .collect();
+
+        let now = Utc::now();
+
+        Self {
+            _id: ObjectId::new(),
+            nonce,
+            created_at: now.timestamp(),
+            expires_at: DateTime::from_millis((now.timestamp() + NONCE_TTL) * 1000),
+        }
+    }
+}
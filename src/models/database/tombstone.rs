@@ -0,0 +1,28 @@
+use chrono::Utc;
+use ethers::utils::{hex::encode, keccak256};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+/// `Tombstone` struct that records that a user's account has been deleted.
+/// 
+/// Only a hash of the wallet address is kept so that no personal data remains after deletion.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Tombstone {
+    /// the object ID of the tombstone in the database
+    pub _id: ObjectId,
+    /// the keccak256 hash of the deleted user's (lowercased) wallet address
+    pub wallet_hash: String,
+    /// when the user was deleted (UNIX format)
+    pub deleted_at: i64,
+}
+
+impl Tombstone {
+    /// Creates a new `Tombstone` instance for the given wallet address.
+    pub fn new(wallet_address: &str) -> Self {
+        Self {
+            _id: ObjectId::new(),
+            wallet_hash: encode(keccak256(wallet_address.to_lowercase())),
+            deleted_at: Utc::now().timestamp(),
+        }
+    }
+}
@@ -1,8 +1,8 @@
-use chrono::{DateTime, Utc};
-use mongodb::{bson::{oid::ObjectId, doc}, Collection};
+use chrono::Utc;
+use mongodb::bson::oid::ObjectId;
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
-use crate::{configs::{get_db, get_collection}, utils::CustomError};
+use crate::configs::KYC_EXPIRY_DAYS;
 
 /// `User` struct that represents a user in the database.
 /// 
@@ -63,28 +63,8 @@ impl User {
         }
     }
 
-    /// Stores a User instance in the database.
-    /// 
-    /// Returns its newly created `ObjectId` if the operation is successful.
-    pub async fn store_user(&self) -> Result<ObjectId, mongodb::error::Error> {
-        let user_col: Collection<User> = get_collection("MainDatabase", "Users").await;
-        let user = user_col.insert_one(self, None).await?;
-
-        match user.inserted_id.as_object_id() {
-            Some(id) => Ok(id),
-            None => Err(mongodb::error::Error::custom("Failed to get inserted ID."))
-        }
-    }
-
-    /// Gets a user's profile from the database.
-    pub async fn get_user(wallet_address: String) -> Result<Self, CustomError> {
-        let user_col: Collection<User> = get_collection("MainDatabase", "Users").await;
-        // case-friendly search
-        let user = user_col.find_one(doc! { "wallet_address": wallet_address.to_lowercase() }, None).await?;
-
-        match user {
-            Some(user) => Ok(user),
-            None => Err(CustomError::DatabaseError("User not found.".to_string()))
-        }
+    /// Checks whether the user has completed KYC and their verification hasn't expired yet (see `KYC_EXPIRY_DAYS`).
+    pub fn is_kyc_valid(&self) -> bool {
+        self.kyc_verified && self.last_kyc_verification + *KYC_EXPIRY_DAYS * 86400 > Utc::now().timestamp()
     }
-}
\ No newline at end of file
+}
@@ -2,12 +2,14 @@ use chrono::{DateTime, Utc};
 use ethers::utils::hex::{encode_prefixed, decode};
 use serde::{Deserialize, Serialize};
 
+use crate::utils::RevertError;
+
 ///////////////////////////////////////////////////////////////////////////////
 ////////// Structs, Impl Blocks and Traits related to Licensee.sol ////////////
 ///////////////////////////////////////////////////////////////////////////////
 
 /// `Licensee` is used to represent a licensee's account information.
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct Licensee {
     /// the licensee's wallet address
     pub wallet_address: String,
@@ -31,6 +33,18 @@ pub struct Licensee {
     pub usable: bool,
 }
 
+/// `LicenseeStatus` represents the status of a licensee account in `Licensee.sol`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LicenseeStatus {
+    /// the address hasn't registered a licensee account
+    NotRegistered,
+    /// the account is registered, but hasn't been approved (i.e. made usable) by an owner yet
+    PendingApproval,
+    /// the account is approved and usable for license applications
+    Approved,
+}
+
 /// An alternative to `Licensee` that returns the raw data from the `LicenseeAccount` struct in the smart contract.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct LicenseeRaw {
@@ -38,4 +52,35 @@ pub struct LicenseeRaw {
     pub data: String,
     /// whether the licensee account is usable or not (i.e. applicable for license applications)
     pub usable: bool,
-}
\ No newline at end of file
+}
+
+/// `LicenseeBatchStatus` represents the outcome of a batch transaction sent to `Licensee.sol`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LicenseeBatchStatus {
+    /// the transaction was mined and succeeded
+    Confirmed,
+    /// the transaction was mined, but reverted
+    Reverted,
+    /// the transaction was sent, but its receipt wasn't fetched within `LICENSEE_RECEIPT_TIMEOUT`; its outcome can be looked up by `tx_hash`
+    Submitted,
+    /// the transaction couldn't be sent (e.g. it reverted during gas estimation or the RPC provider failed) or was dropped from the mempool
+    Failed,
+}
+
+/// `LicenseeBatchOutcome` reports the outcome of a single batch of an `approveAccounts`, `updateAccounts` or `removeAccounts` call.
+#[derive(Debug, Clone, Serialize)]
+pub struct LicenseeBatchOutcome {
+    /// the index of the batch (starting from 0)
+    pub batch: usize,
+    /// the (lowercased) licensee addresses included in the batch
+    pub licensees: Vec<String>,
+    /// the hash of the batch's transaction, if it was sent
+    pub tx_hash: Option<String>,
+    /// the outcome of the batch
+    pub status: LicenseeBatchStatus,
+    /// (optional) why the batch failed
+    pub error: Option<String>,
+    /// (optional) the decoded contract error, if the batch failed because the contract reverted
+    pub revert: Option<RevertError>,
+}
@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+
+/// `RefreshAccessToken` struct that represents the request body of the `/auth/refresh` route endpoint.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RefreshAccessToken {
+    /// the session token returned at login
+    pub refresh_token: String,
+}
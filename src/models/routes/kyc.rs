@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::KycDocument;
+
+/// `SubmitKyc` struct that represents the request body of the `/kyc/submit` route endpoint.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SubmitKyc {
+    /// the metadata of the documents being submitted
+    pub documents: Vec<KycDocument>,
+}
+
+/// `ReviewKyc` struct that represents the request body of the `/kyc/:id/review` route endpoint.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReviewKyc {
+    /// whether the submission is approved or rejected
+    pub approved: bool,
+    /// (optional) the reason for the decision; required when rejecting
+    pub reason: Option<String>,
+}
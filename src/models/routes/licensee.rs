@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+
+/// `RegisterLicensee` struct that represents the request body of the `/licensee/register-params` route endpoint.
+/// 
+/// NOTE: the wallet address is taken from the authenticated session, since `registerAccount` must be called by that same wallet.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RegisterLicensee {
+    /// the licensee's full name
+    pub name: String,
+    /// the licensee's date of birth (RFC3339 format)
+    pub dob: String,
+    /// the licensee's home/company address
+    pub address: String,
+    /// the licensee's email address
+    pub email: String,
+    /// the licensee's phone number
+    pub phone: String,
+    /// (optional) the licensee's company name
+    pub company: Option<String>,
+    /// the licensee's nationality
+    pub nationality: String,
+    /// the licensee's country of application
+    pub country_of_application: String,
+}
+
+/// `LicenseeBatch` struct that represents the request body of the `/licensee/approve` and `/licensee/remove` route endpoints.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LicenseeBatch {
+    /// the wallet addresses of the licensees to approve/remove
+    pub licensees: Vec<String>,
+}
+
+/// `UpdateLicensee` represents a single licensee account to update via the `/licensee/update` route endpoint.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateLicensee {
+    /// the wallet address of the licensee
+    pub wallet_address: String,
+    /// the licensee's new account information
+    #[serde(flatten)]
+    pub params: RegisterLicensee,
+}
+
+/// `UpdateLicenseeBatch` struct that represents the request body of the `/licensee/update` route endpoint.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateLicenseeBatch {
+    /// the licensee accounts to update
+    pub accounts: Vec<UpdateLicensee>,
+}
@@ -1,13 +1,16 @@
 use serde_json::Value;
 use serde::{Deserialize, Serialize};
 
+use crate::utils::DateInput;
+
 /// `CreateUser` struct that represents the request body of the `/user/create` route endpoint.
 /// 
 /// These fields come from the `User` and `Session` structs that are needed to call the `create_user` function.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateUser {
     pub wallet_address: String,
-    pub expiration_date: i64,
+    /// when the signed SIWE message expires (RFC3339, exactly as signed)
+    pub expiration_time: String,
     pub chain_id: u32,
     pub domain: String,
     pub nonce: String,
@@ -16,4 +19,49 @@ pub struct CreateUser {
     pub profile_id: String,
     pub uri: String,
     pub version: u8,
-}
\ No newline at end of file
+    /// (optional) the statement included in the signed SIWE message
+    pub statement: Option<String>,
+    /// when the signed SIWE message was issued (RFC3339, exactly as signed)
+    pub issued_at: String,
+}
+
+/// `LoginUser` represents the request body of the `/auth/login` route endpoint.
+/// 
+/// It carries the same SIWE fields as `CreateUser`, but is used for both new and returning users.
+pub type LoginUser = CreateUser;
+
+/// `UpdateUser` struct that represents the request body of the `/user/me` (PATCH) route endpoint.
+/// 
+/// Only the fields that are provided will be updated; text fields can't be set to empty values.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateUser {
+    pub name: Option<String>,
+    /// either a UNIX timestamp or an RFC3339 compliant date string
+    pub dob: Option<DateInput>,
+    pub email: Option<String>,
+    /// must be E.164 compliant (e.g. `+6512345678`)
+    pub phone: Option<String>,
+    pub address: Option<String>,
+    pub company: Option<String>,
+}
+
+/// `ListUsersQuery` struct that represents the query parameters of the `/user` (GET) route endpoint.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListUsersQuery {
+    /// the page to return, starting from 1 (defaults to 1)
+    pub page: Option<usize>,
+    /// the amount of users per page (defaults to 20, max. 100)
+    pub page_size: Option<usize>,
+    /// the field to sort by; either `created_at` (default) or `updated_at`
+    pub sort_by: Option<String>,
+    /// the sort order; either `asc` or `desc` (default)
+    pub sort_order: Option<String>,
+    /// only return users with the given KYC status
+    pub kyc_verified: Option<bool>,
+    /// only return users whose company name contains this (case-insensitive)
+    pub company: Option<String>,
+    /// only return users created at or after this UNIX timestamp
+    pub created_from: Option<i64>,
+    /// only return users created at or before this UNIX timestamp
+    pub created_to: Option<i64>,
+}
@@ -1,20 +1,33 @@
-use chrono::{DateTime, Utc};
-use mongodb::{bson::oid::ObjectId, Collection};
-use crate::configs::get_collection;
+use chrono::Utc;
+use ethers::{core::rand::{thread_rng, Rng}, utils::hex::encode};
+use sha2::{Digest, Sha256};
+use mongodb::bson::{oid::ObjectId, DateTime};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::{models::LoginUser, configs::MAX_SESSION_TTL};
+
 /// `Session` struct that represents a session that gets created when a user logs in via the webapp.
 /// 
 /// All dates and timestamps are stored in UNIX format.
 /// 
 /// Since this uses Moralis' NextAuth provider, the majority of these fields will follow SIWE's EIP4361 standard.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Session {
     /// the object ID of the session in the database
     pub _id: ObjectId,
+    /// the SHA-256 hash (hex-encoded) of the random bearer token that authenticates requests made with this session
+    /// 
+    /// the token itself is never stored; it is only returned once, when the session is created.
+    #[serde(default)]
+    pub token_hash: String,
     /// the session's expiration date
     pub expiration_date: i64,
+    /// the session's expiration date as a BSON date, used by the TTL index to purge expired sessions.
+    /// 
+    /// sessions created before this field was introduced don't have it and must be cleaned up manually.
+    #[serde(default)]
+    pub expires_at: Option<DateTime>,
     /// the user's wallet address tied to this session
     pub wallet_address: String,
     /// the id of the chain the user is on when they logged in
@@ -36,44 +49,67 @@ pub struct Session {
 }
 
 impl Session {
-    /// Creates a new `Session` instance.
-    pub fn new(
-        expiration_date: i64,
-        wallet_address: String,
-        chain_id: u32,
-        domain: String,
-        nonce: String,
-        signature: String,
-        payload: Option<Value>,
-        profile_id: String,
-        uri: String,
-        version: u8
-    ) -> Self {
-        Self {
+    /// Creates a new `Session` instance from a (verified) login request.
+    /// 
+    /// `expiration_date` is the UNIX timestamp of the signed SIWE message's expiration time; it is capped at `MAX_SESSION_TTL` from now,
+    /// since the client chooses it.
+    /// Returns the session along with its bearer token, which can't be recovered afterwards.
+    pub fn new(login: LoginUser, expiration_date: i64) -> (Self, String) {
+        let token = encode(thread_rng().gen::<[u8; 32]>());
+        let expiration_date = expiration_date.min(Utc::now().timestamp() + MAX_SESSION_TTL);
+
+        let session = Self {
             _id: ObjectId::new(),
+            token_hash: Session::hash_token(&token),
             expiration_date,
-            wallet_address,
-            chain_id,
-            domain,
-            nonce,
-            signature,
-            payload,
-            profile_id,
-            uri,
-            version
-        }
+            expires_at: Some(DateTime::from_millis(expiration_date.saturating_mul(1000))),
+            wallet_address: login.wallet_address.to_lowercase(),
+            chain_id: login.chain_id,
+            domain: login.domain,
+            nonce: login.nonce,
+            signature: login.signature,
+            payload: login.payload,
+            profile_id: login.profile_id,
+            uri: login.uri,
+            version: login.version
+        };
+
+        (session, token)
     }
 
-    /// Stores a `Session` instance in the database.
-    /// 
-    /// Returns its newly created `ObjectId` if the operation is successful.
-    pub async fn store_session(&self) -> Result<ObjectId, mongodb::error::Error> {
-        let session_col: Collection<Session> = get_collection("MainDatabase", "Sessions").await;
-        let session = session_col.insert_one(self, None).await?;
+    /// Hashes a bearer token the way it is stored in `token_hash`.
+    pub fn hash_token(token: &str) -> String {
+        encode(Sha256::digest(token.as_bytes()))
+    }
+}
 
-        match session.inserted_id.as_object_id() {
-            Some(id) => Ok(id.clone()),
-            None => Err(mongodb::error::Error::custom("Failed to get inserted ID."))
+/// `SessionInfo` is a public view of a `Session`, leaving out its token and signature.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SessionInfo {
+    /// the object ID of the session in the database
+    pub id: ObjectId,
+    /// the session's expiration date
+    pub expiration_date: i64,
+    /// the id of the chain the user was on when they logged in
+    pub chain_id: u32,
+    /// the domain that requested the login
+    pub domain: String,
+    /// the uri that the user logged in from
+    pub uri: String,
+    /// whether this is the session used to make the current request
+    pub current: bool,
+}
+
+impl SessionInfo {
+    /// Creates a `SessionInfo` instance from a `Session`, flagging it if it's the session used to make the current request.
+    pub fn from_session(session: &Session, current_session_id: ObjectId) -> Self {
+        Self {
+            id: session._id,
+            expiration_date: session.expiration_date,
+            chain_id: session.chain_id,
+            domain: session.domain.clone(),
+            uri: session.uri.clone(),
+            current: session._id == current_session_id,
         }
     }
 }
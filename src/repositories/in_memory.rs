@@ -0,0 +1,427 @@
+use std::{collections::HashMap, sync::RwLock};
+
+use async_trait::async_trait;
+use chrono::Utc;
+use mongodb::bson::{oid::ObjectId, DateTime};
+
+use crate::{models::{User, Session, Nonce, KycSubmission, KycStatus, Tombstone, LicenseeRecord}, utils::CustomError};
+
+use super::{UserRepository, UserUpdate, UserFilter, UserSortField, SessionRepository, NonceRepository, KycRepository, TombstoneRepository, LicenseeRecordRepository};
+
+/// `InMemoryUserRepository` keeps users in memory, keyed by their (lowercased) wallet address.
+/// 
+/// NOTE: this is meant for running handlers without MongoDB (e.g. in tests); nothing is persisted.
+#[derive(Debug, Default)]
+pub struct InMemoryUserRepository {
+    users: RwLock<HashMap<String, User>>,
+}
+
+impl InMemoryUserRepository {
+    /// Creates a new, empty `InMemoryUserRepository` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl UserRepository for InMemoryUserRepository {
+    async fn store_user(&self, user: &User) -> Result<ObjectId, CustomError> {
+        let mut users = self.users.write().unwrap();
+        let wallet_address = user.wallet_address.to_lowercase();
+
+        if users.contains_key(&wallet_address) {
+            return Err(CustomError::Conflict("User already exists.".to_string()));
+        }
+
+        let mut user = user.clone();
+        let id = *user._id.get_or_insert_with(ObjectId::new);
+        users.insert(wallet_address, user);
+
+        Ok(id)
+    }
+
+    async fn get_user(&self, wallet_address: &str) -> Result<User, CustomError> {
+        match self.users.read().unwrap().get(&wallet_address.to_lowercase()) {
+            Some(user) => Ok(user.clone()),
+            None => Err(CustomError::NotFound("User not found.".to_string()))
+        }
+    }
+
+    async fn user_exists(&self, wallet_address: &str) -> Result<bool, CustomError> {
+        Ok(self.users.read().unwrap().contains_key(&wallet_address.to_lowercase()))
+    }
+
+    async fn upsert_user(&self, wallet_address: &str) -> Result<ObjectId, CustomError> {
+        let mut users = self.users.write().unwrap();
+        let user = users
+            .entry(wallet_address.to_lowercase())
+            .or_insert_with(|| User::new(wallet_address.to_string()));
+        user.updated_at = Utc::now().timestamp();
+
+        Ok(*user._id.get_or_insert_with(ObjectId::new))
+    }
+
+    async fn update_user(&self, wallet_address: &str, update: UserUpdate) -> Result<User, CustomError> {
+        let mut users = self.users.write().unwrap();
+        let user = users
+            .get_mut(&wallet_address.to_lowercase())
+            .ok_or(CustomError::NotFound("User not found.".to_string()))?;
+
+        if let Some(name) = update.name { user.name = Some(name); }
+        if let Some(dob) = update.dob { user.dob = Some(dob); }
+        if let Some(email) = update.email { user.email = Some(email); }
+        if let Some(phone) = update.phone { user.phone = Some(phone); }
+        if let Some(address) = update.address { user.address = Some(address); }
+        if let Some(company) = update.company { user.company = Some(company); }
+        if let Some(kyc_verified) = update.kyc_verified { user.kyc_verified = kyc_verified; }
+        if let Some(last_kyc_verification) = update.last_kyc_verification { user.last_kyc_verification = last_kyc_verification; }
+        user.updated_at = Utc::now().timestamp();
+
+        Ok(user.clone())
+    }
+
+    async fn list_users(&self, filter: UserFilter) -> Result<(Vec<User>, u64), CustomError> {
+        let company = filter.company.as_ref().map(|company| company.to_lowercase());
+
+        let mut users: Vec<User> = self.users.read().unwrap()
+            .values()
+            .filter(|user| filter.kyc_verified.is_none_or(|kyc_verified| user.kyc_verified == kyc_verified))
+            .filter(|user| company.as_ref().is_none_or(|company| {
+                user.company.as_ref().is_some_and(|c| c.to_lowercase().contains(company))
+            }))
+            .filter(|user| filter.created_from.is_none_or(|from| user.created_at >= from))
+            .filter(|user| filter.created_to.is_none_or(|to| user.created_at <= to))
+            .cloned()
+            .collect();
+
+        users.sort_by_key(|user| match filter.sort_by {
+            UserSortField::CreatedAt => user.created_at,
+            UserSortField::UpdatedAt => user.updated_at,
+        });
+
+        if !filter.ascending {
+            users.reverse();
+        }
+
+        let total_items = users.len() as u64;
+        let users = users
+            .into_iter()
+            .skip(filter.skip as usize)
+            .take(filter.limit.max(0) as usize)
+            .collect();
+
+        Ok((users, total_items))
+    }
+
+    async fn delete_user(&self, wallet_address: &str) -> Result<bool, CustomError> {
+        Ok(self.users.write().unwrap().remove(&wallet_address.to_lowercase()).is_some())
+    }
+}
+
+/// `InMemorySessionRepository` keeps sessions in memory.
+/// 
+/// NOTE: this is meant for running handlers without MongoDB (e.g. in tests); nothing is persisted.
+#[derive(Debug, Default)]
+pub struct InMemorySessionRepository {
+    sessions: RwLock<Vec<Session>>,
+}
+
+impl InMemorySessionRepository {
+    /// Creates a new, empty `InMemorySessionRepository` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl SessionRepository for InMemorySessionRepository {
+    async fn store_session(&self, session: &Session) -> Result<ObjectId, CustomError> {
+        self.sessions.write().unwrap().push(session.clone());
+
+        Ok(session._id)
+    }
+
+    async fn get_session(&self, session_id: ObjectId) -> Result<Option<Session>, CustomError> {
+        Ok(self.sessions.read().unwrap().iter().find(|session| session._id == session_id).cloned())
+    }
+
+    async fn get_session_by_token_hash(&self, token_hash: &str) -> Result<Option<Session>, CustomError> {
+        if token_hash.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(self.sessions.read().unwrap().iter().find(|session| session.token_hash == token_hash).cloned())
+    }
+
+    async fn get_active_sessions(&self, wallet_address: &str) -> Result<Vec<Session>, CustomError> {
+        let now = Utc::now().timestamp();
+
+        Ok(self.sessions.read().unwrap()
+            .iter()
+            .filter(|session| session.wallet_address.eq_ignore_ascii_case(wallet_address) && session.expiration_date > now)
+            .cloned()
+            .collect())
+    }
+
+    async fn get_sessions(&self, wallet_address: &str) -> Result<Vec<Session>, CustomError> {
+        Ok(self.sessions.read().unwrap()
+            .iter()
+            .filter(|session| session.wallet_address.eq_ignore_ascii_case(wallet_address))
+            .cloned()
+            .collect())
+    }
+
+    async fn revoke_session(&self, session_id: ObjectId, wallet_address: &str) -> Result<bool, CustomError> {
+        let mut sessions = self.sessions.write().unwrap();
+        let count = sessions.len();
+        sessions.retain(|session| !(session._id == session_id && session.wallet_address.eq_ignore_ascii_case(wallet_address)));
+
+        Ok(sessions.len() < count)
+    }
+
+    async fn revoke_all_sessions(&self, wallet_address: &str) -> Result<u64, CustomError> {
+        let mut sessions = self.sessions.write().unwrap();
+        let count = sessions.len();
+        sessions.retain(|session| !session.wallet_address.eq_ignore_ascii_case(wallet_address));
+
+        Ok((count - sessions.len()) as u64)
+    }
+}
+
+/// `InMemoryNonceRepository` keeps issued nonces in memory, mapped to when they expire.
+/// 
+/// NOTE: this is meant for running handlers without MongoDB (e.g. in tests); nothing is persisted.
+#[derive(Debug, Default)]
+pub struct InMemoryNonceRepository {
+    nonces: RwLock<HashMap<String, DateTime>>,
+}
+
+impl InMemoryNonceRepository {
+    /// Creates a new, empty `InMemoryNonceRepository` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl NonceRepository for InMemoryNonceRepository {
+    async fn store_nonce(&self, nonce: &Nonce) -> Result<(), CustomError> {
+        let mut nonces = self.nonces.write().unwrap();
+
+        if nonces.contains_key(&nonce.nonce) {
+            return Err(CustomError::Conflict("Nonce already exists.".to_string()));
+        }
+
+        nonces.insert(nonce.nonce.clone(), nonce.expires_at);
+
+        Ok(())
+    }
+
+    async fn consume_nonce(&self, nonce: &str) -> Result<(), CustomError> {
+        match self.nonces.write().unwrap().remove(nonce) {
+            Some(expires_at) if expires_at > DateTime::now() => Ok(()),
+            _ => Err(CustomError::Auth("Nonce is invalid, expired or has already been used.".to_string()))
+        }
+    }
+}
+
+/// `InMemoryKycRepository` keeps KYC submissions in memory.
+/// 
+/// NOTE: this is meant for running handlers without MongoDB (e.g. in tests); nothing is persisted.
+#[derive(Debug, Default)]
+pub struct InMemoryKycRepository {
+    submissions: RwLock<Vec<KycSubmission>>,
+}
+
+impl InMemoryKycRepository {
+    /// Creates a new, empty `InMemoryKycRepository` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl KycRepository for InMemoryKycRepository {
+    async fn store_submission(&self, submission: &KycSubmission) -> Result<ObjectId, CustomError> {
+        let mut submissions = self.submissions.write().unwrap();
+
+        // mirrors the partial unique index that allows at most one pending submission per wallet address.
+        let has_pending = submissions.iter().any(|existing| {
+            existing.status == KycStatus::Pending && existing.wallet_address.eq_ignore_ascii_case(&submission.wallet_address)
+        });
+
+        if submission.status == KycStatus::Pending && has_pending {
+            return Err(CustomError::Conflict("A document with the same unique key already exists.".to_string()));
+        }
+
+        submissions.push(submission.clone());
+
+        Ok(submission._id)
+    }
+
+    async fn get_submissions(&self, wallet_address: &str) -> Result<Vec<KycSubmission>, CustomError> {
+        let mut submissions: Vec<KycSubmission> = self.submissions.read().unwrap()
+            .iter()
+            .filter(|submission| submission.wallet_address.eq_ignore_ascii_case(wallet_address))
+            .cloned()
+            .collect();
+
+        submissions.sort_by_key(|submission| std::cmp::Reverse(submission.submitted_at));
+
+        Ok(submissions)
+    }
+
+    async fn get_submission(&self, submission_id: ObjectId) -> Result<KycSubmission, CustomError> {
+        match self.submissions.read().unwrap().iter().find(|submission| submission._id == submission_id) {
+            Some(submission) => Ok(submission.clone()),
+            None => Err(CustomError::NotFound("KYC submission not found.".to_string()))
+        }
+    }
+
+    async fn get_submissions_by_status(&self, status: KycStatus) -> Result<Vec<KycSubmission>, CustomError> {
+        let mut submissions: Vec<KycSubmission> = self.submissions.read().unwrap()
+            .iter()
+            .filter(|submission| submission.status == status)
+            .cloned()
+            .collect();
+
+        submissions.sort_by_key(|submission| submission.submitted_at);
+
+        Ok(submissions)
+    }
+
+    async fn review_submission(
+        &self,
+        submission_id: ObjectId,
+        status: KycStatus,
+        reviewer: String,
+        reason: Option<String>
+    ) -> Result<KycSubmission, CustomError> {
+        let mut submissions = self.submissions.write().unwrap();
+        let submission = submissions
+            .iter_mut()
+            .find(|submission| submission._id == submission_id && submission.status == KycStatus::Pending)
+            .ok_or(CustomError::NotFound("Pending KYC submission not found.".to_string()))?;
+
+        submission.status = status;
+        submission.reviewed_at = Some(Utc::now().timestamp());
+        submission.reviewer = Some(reviewer.to_lowercase());
+        submission.reason = reason;
+
+        Ok(submission.clone())
+    }
+
+    async fn delete_submissions(&self, wallet_address: &str) -> Result<u64, CustomError> {
+        let mut submissions = self.submissions.write().unwrap();
+        let count = submissions.len();
+        submissions.retain(|submission| !submission.wallet_address.eq_ignore_ascii_case(wallet_address));
+
+        Ok((count - submissions.len()) as u64)
+    }
+}
+
+/// `InMemoryTombstoneRepository` keeps tombstones in memory.
+/// 
+/// NOTE: this is meant for running handlers without MongoDB (e.g. in tests); nothing is persisted.
+#[derive(Debug, Default)]
+pub struct InMemoryTombstoneRepository {
+    tombstones: RwLock<Vec<String>>,
+}
+
+impl InMemoryTombstoneRepository {
+    /// Creates a new, empty `InMemoryTombstoneRepository` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Checks whether a tombstone was stored for the given wallet address.
+    pub fn has_tombstone(&self, wallet_address: &str) -> bool {
+        let wallet_hash = Tombstone::new(wallet_address).wallet_hash;
+
+        self.tombstones.read().unwrap().contains(&wallet_hash)
+    }
+}
+
+#[async_trait]
+impl TombstoneRepository for InMemoryTombstoneRepository {
+    async fn store_tombstone(&self, tombstone: &Tombstone) -> Result<(), CustomError> {
+        self.tombstones.write().unwrap().push(tombstone.wallet_hash.clone());
+
+        Ok(())
+    }
+}
+
+/// `InMemoryLicenseeRecordRepository` keeps licensee records in memory, keyed by their `ObjectId`.
+/// 
+/// NOTE: this is meant for running handlers without MongoDB (e.g. in tests); nothing is persisted.
+#[derive(Debug, Default)]
+pub struct InMemoryLicenseeRecordRepository {
+    records: RwLock<HashMap<ObjectId, LicenseeRecord>>,
+}
+
+impl InMemoryLicenseeRecordRepository {
+    /// Creates a new, empty `InMemoryLicenseeRecordRepository` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl LicenseeRecordRepository for InMemoryLicenseeRecordRepository {
+    async fn store_record(&self, record: &LicenseeRecord) -> Result<ObjectId, CustomError> {
+        self.records.write().unwrap().insert(record._id, record.clone());
+
+        Ok(record._id)
+    }
+
+    async fn upsert_pending_record(&self, record: &LicenseeRecord) -> Result<ObjectId, CustomError> {
+        let mut records = self.records.write().unwrap();
+        let pending = records.values()
+            .find(|stored| stored.pending && stored.wallet_address == record.wallet_address)
+            .map(|stored| stored._id);
+
+        let record_id = pending.unwrap_or(record._id);
+        records.insert(record_id, LicenseeRecord { _id: record_id, pending: true, ..record.clone() });
+
+        Ok(record_id)
+    }
+
+    async fn confirm_record(&self, record_id: ObjectId) -> Result<(), CustomError> {
+        if let Some(record) = self.records.write().unwrap().get_mut(&record_id) {
+            record.pending = false;
+        }
+
+        Ok(())
+    }
+
+    async fn get_record(&self, record_id: ObjectId) -> Result<LicenseeRecord, CustomError> {
+        match self.records.read().unwrap().get(&record_id) {
+            Some(record) => Ok(record.clone()),
+            None => Err(CustomError::NotFound("Licensee record not found; it may have been deleted.".to_string()))
+        }
+    }
+
+    async fn delete_records(&self, wallet_address: &str) -> Result<u64, CustomError> {
+        let mut records = self.records.write().unwrap();
+        let count = records.len();
+        records.retain(|_, record| !record.wallet_address.eq_ignore_ascii_case(wallet_address));
+
+        Ok((count - records.len()) as u64)
+    }
+
+    async fn delete_records_by_id(&self, record_ids: &[ObjectId]) -> Result<u64, CustomError> {
+        let mut records = self.records.write().unwrap();
+        let count = records.len();
+        records.retain(|record_id, _| !record_ids.contains(record_id));
+
+        Ok((count - records.len()) as u64)
+    }
+
+    async fn delete_superseded_records(&self, wallet_address: &str, current_id: ObjectId) -> Result<u64, CustomError> {
+        let mut records = self.records.write().unwrap();
+        let count = records.len();
+        records.retain(|record_id, record| *record_id == current_id || !record.wallet_address.eq_ignore_ascii_case(wallet_address));
+
+        Ok((count - records.len()) as u64)
+    }
+}
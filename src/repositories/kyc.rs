@@ -0,0 +1,151 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use futures::stream::TryStreamExt;
+use mongodb::{bson::{oid::ObjectId, doc, to_bson}, Collection, Database, IndexModel, options::{FindOneAndUpdateOptions, FindOptions, IndexOptions, ReturnDocument}};
+
+use crate::{models::{KycSubmission, KycStatus}, configs::get_collection, utils::CustomError};
+
+/// `KycRepository` abstracts how `KycSubmission` instances are stored.
+#[async_trait]
+pub trait KycRepository: Send + Sync {
+    /// Stores a new submission, returning its `ObjectId`.
+    ///
+    /// Returns a `Conflict` error if the user already has a pending submission.
+    async fn store_submission(&self, submission: &KycSubmission) -> Result<ObjectId, CustomError>;
+
+    /// Gets all of a user's KYC submissions, newest first.
+    async fn get_submissions(&self, wallet_address: &str) -> Result<Vec<KycSubmission>, CustomError>;
+
+    /// Gets a KYC submission by its `ObjectId`, returning an error if it doesn't exist.
+    async fn get_submission(&self, submission_id: ObjectId) -> Result<KycSubmission, CustomError>;
+
+    /// Gets all KYC submissions with the given status, oldest first.
+    async fn get_submissions_by_status(&self, status: KycStatus) -> Result<Vec<KycSubmission>, CustomError>;
+
+    /// Marks a pending submission as approved or rejected.
+    ///
+    /// Returns the reviewed submission, or an error if it doesn't exist or was already reviewed.
+    async fn review_submission(
+        &self,
+        submission_id: ObjectId,
+        status: KycStatus,
+        reviewer: String,
+        reason: Option<String>
+    ) -> Result<KycSubmission, CustomError>;
+
+    /// Deletes all of a user's KYC submissions, returning the amount deleted.
+    async fn delete_submissions(&self, wallet_address: &str) -> Result<u64, CustomError>;
+}
+
+/// `MongoKycRepository` stores KYC submissions in the `KycSubmissions` collection.
+#[derive(Debug, Clone)]
+pub struct MongoKycRepository {
+    submissions: Collection<KycSubmission>,
+}
+
+impl MongoKycRepository {
+    /// Creates a new `MongoKycRepository` instance from the given database.
+    pub fn new(db: &Database) -> Self {
+        Self {
+            submissions: get_collection(db, "KycSubmissions"),
+        }
+    }
+
+    /// Creates the indexes required by the `KycSubmissions` collection.
+    ///
+    /// This includes a partial unique index that allows at most one pending submission per wallet address.
+    pub async fn create_indexes(&self) -> Result<(), CustomError> {
+        let wallet_index = IndexModel::builder()
+            .keys(doc! { "wallet_address": 1, "submitted_at": -1 })
+            .build();
+        let status_index = IndexModel::builder()
+            .keys(doc! { "status": 1, "submitted_at": 1 })
+            .build();
+
+        let pending_index = IndexModel::builder()
+            .keys(doc! { "wallet_address": 1 })
+            .options(
+                IndexOptions::builder()
+                    .name("wallet_address_pending_unique".to_string())
+                    .unique(true)
+                    .partial_filter_expression(doc! { "status": "pending" })
+                    .build()
+            )
+            .build();
+
+        self.submissions.create_indexes([wallet_index, status_index, pending_index], None).await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl KycRepository for MongoKycRepository {
+    async fn store_submission(&self, submission: &KycSubmission) -> Result<ObjectId, CustomError> {
+        self.submissions.insert_one(submission, None).await?;
+
+        Ok(submission._id)
+    }
+
+    async fn get_submissions(&self, wallet_address: &str) -> Result<Vec<KycSubmission>, CustomError> {
+        let options = FindOptions::builder().sort(doc! { "submitted_at": -1 }).build();
+        let submissions = self.submissions.find(doc! { "wallet_address": wallet_address.to_lowercase() }, options).await?;
+
+        Ok(submissions.try_collect().await?)
+    }
+
+    async fn get_submission(&self, submission_id: ObjectId) -> Result<KycSubmission, CustomError> {
+        match self.submissions.find_one(doc! { "_id": submission_id }, None).await? {
+            Some(submission) => Ok(submission),
+            None => Err(CustomError::NotFound("KYC submission not found.".to_string()))
+        }
+    }
+
+    async fn get_submissions_by_status(&self, status: KycStatus) -> Result<Vec<KycSubmission>, CustomError> {
+        let status = to_bson(&status).map_err(|e| CustomError::Database(e.to_string()))?;
+        let options = FindOptions::builder().sort(doc! { "submitted_at": 1 }).build();
+        let submissions = self.submissions.find(doc! { "status": status }, options).await?;
+
+        Ok(submissions.try_collect().await?)
+    }
+
+    async fn review_submission(
+        &self,
+        submission_id: ObjectId,
+        status: KycStatus,
+        reviewer: String,
+        reason: Option<String>
+    ) -> Result<KycSubmission, CustomError> {
+        let pending = to_bson(&KycStatus::Pending).map_err(|e| CustomError::Database(e.to_string()))?;
+        let status = to_bson(&status).map_err(|e| CustomError::Database(e.to_string()))?;
+
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+
+        // only pending submissions can be reviewed, so that a decision can't be overwritten.
+        let submission = self.submissions.find_one_and_update(
+            doc! { "_id": submission_id, "status": pending },
+            doc! {
+                "$set": {
+                    "status": status,
+                    "reviewed_at": Utc::now().timestamp(),
+                    "reviewer": reviewer.to_lowercase(),
+                    "reason": reason
+                }
+            },
+            options
+        ).await?;
+
+        match submission {
+            Some(submission) => Ok(submission),
+            None => Err(CustomError::NotFound("Pending KYC submission not found.".to_string()))
+        }
+    }
+
+    async fn delete_submissions(&self, wallet_address: &str) -> Result<u64, CustomError> {
+        let result = self.submissions.delete_many(doc! { "wallet_address": wallet_address.to_lowercase() }, None).await?;
+
+        Ok(result.deleted_count)
+    }
+}
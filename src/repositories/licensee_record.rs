@@ -0,0 +1,138 @@
+use async_trait::async_trait;
+use mongodb::{bson::{oid::ObjectId, doc, to_bson}, Collection, Database, IndexModel, options::{FindOneAndUpdateOptions, IndexOptions, ReturnDocument}};
+
+use crate::{models::LicenseeRecord, configs::get_collection, utils::CustomError};
+
+/// `LicenseeRecordRepository` abstracts how the off-chain `LicenseeRecord` instances (see `LICENSEE_STORAGE_MODE`) are stored.
+#[async_trait]
+pub trait LicenseeRecordRepository: Send + Sync {
+    /// Stores a new licensee record, returning its `ObjectId`.
+    async fn store_record(&self, record: &LicenseeRecord) -> Result<ObjectId, CustomError>;
+
+    /// Stores a licensee's pending record, replacing their previous pending record (if any) but keeping its `ObjectId`.
+    ///
+    /// Returns the `ObjectId` the record was stored under, which is `record._id` only if the licensee had no pending record.
+    async fn upsert_pending_record(&self, record: &LicenseeRecord) -> Result<ObjectId, CustomError>;
+
+    /// Marks a pending record as confirmed, so that it can no longer be replaced.
+    async fn confirm_record(&self, record_id: ObjectId) -> Result<(), CustomError>;
+
+    /// Gets a licensee record by its `ObjectId`, returning an error if it doesn't exist.
+    async fn get_record(&self, record_id: ObjectId) -> Result<LicenseeRecord, CustomError>;
+
+    /// Deletes all of a licensee's records, returning the amount deleted.
+    async fn delete_records(&self, wallet_address: &str) -> Result<u64, CustomError>;
+
+    /// Deletes the records with the given `ObjectId`s, returning the amount deleted.
+    async fn delete_records_by_id(&self, record_ids: &[ObjectId]) -> Result<u64, CustomError>;
+
+    /// Deletes all of a licensee's records except `current_id` (i.e. the ones superseded by it), returning the amount deleted.
+    async fn delete_superseded_records(&self, wallet_address: &str, current_id: ObjectId) -> Result<u64, CustomError>;
+}
+
+/// `MongoLicenseeRecordRepository` stores licensee records in the `LicenseeRecords` collection.
+#[derive(Debug, Clone)]
+pub struct MongoLicenseeRecordRepository {
+    records: Collection<LicenseeRecord>,
+}
+
+impl MongoLicenseeRecordRepository {
+    /// Creates a new `MongoLicenseeRecordRepository` instance from the given database.
+    pub fn new(db: &Database) -> Self {
+        Self {
+            records: get_collection(db, "LicenseeRecords"),
+        }
+    }
+
+    /// Creates the indexes required by the `LicenseeRecords` collection.
+    ///
+    /// This includes a partial unique index that allows at most one pending record per wallet address.
+    pub async fn create_indexes(&self) -> Result<(), CustomError> {
+        let wallet_index = IndexModel::builder()
+            .keys(doc! { "wallet_address": 1 })
+            .build();
+
+        let pending_index = IndexModel::builder()
+            .keys(doc! { "wallet_address": 1 })
+            .options(
+                IndexOptions::builder()
+                    .name("wallet_address_pending_unique".to_string())
+                    .unique(true)
+                    .partial_filter_expression(doc! { "pending": true })
+                    .build()
+            )
+            .build();
+
+        self.records.create_indexes([wallet_index, pending_index], None).await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl LicenseeRecordRepository for MongoLicenseeRecordRepository {
+    async fn store_record(&self, record: &LicenseeRecord) -> Result<ObjectId, CustomError> {
+        self.records.insert_one(record, None).await?;
+
+        Ok(record._id)
+    }
+
+    async fn upsert_pending_record(&self, record: &LicenseeRecord) -> Result<ObjectId, CustomError> {
+        let licensee = to_bson(&record.licensee).map_err(|e| CustomError::Database(e.to_string()))?;
+
+        let options = FindOneAndUpdateOptions::builder()
+            .upsert(true)
+            .return_document(ReturnDocument::After)
+            .build();
+
+        // the `ObjectId` of an existing pending record is kept, since a record's `_id` can't be changed.
+        let stored = self.records.find_one_and_update(
+            doc! { "wallet_address": &record.wallet_address, "pending": true },
+            doc! {
+                "$set": {
+                    "licensee": licensee,
+                    "salt": &record.salt,
+                    "created_at": record.created_at
+                },
+                "$setOnInsert": { "_id": record._id }
+            },
+            options
+        ).await?;
+
+        match stored {
+            Some(stored) => Ok(stored._id),
+            None => Err(CustomError::Database("Pending licensee record was not stored.".to_string()))
+        }
+    }
+
+    async fn confirm_record(&self, record_id: ObjectId) -> Result<(), CustomError> {
+        self.records.update_one(doc! { "_id": record_id }, doc! { "$set": { "pending": false } }, None).await?;
+
+        Ok(())
+    }
+
+    async fn get_record(&self, record_id: ObjectId) -> Result<LicenseeRecord, CustomError> {
+        match self.records.find_one(doc! { "_id": record_id }, None).await? {
+            Some(record) => Ok(record),
+            None => Err(CustomError::NotFound("Licensee record not found; it may have been deleted.".to_string()))
+        }
+    }
+
+    async fn delete_records(&self, wallet_address: &str) -> Result<u64, CustomError> {
+        let result = self.records.delete_many(doc! { "wallet_address": wallet_address.to_lowercase() }, None).await?;
+
+        Ok(result.deleted_count)
+    }
+
+    async fn delete_records_by_id(&self, record_ids: &[ObjectId]) -> Result<u64, CustomError> {
+        let result = self.records.delete_many(doc! { "_id": { "$in": record_ids } }, None).await?;
+
+        Ok(result.deleted_count)
+    }
+
+    async fn delete_superseded_records(&self, wallet_address: &str, current_id: ObjectId) -> Result<u64, CustomError> {
+        let result = self.records.delete_many(doc! { "wallet_address": wallet_address.to_lowercase(), "_id": { "$ne": current_id } }, None).await?;
+
+        Ok(result.deleted_count)
+    }
+}
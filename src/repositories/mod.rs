@@ -0,0 +1,17 @@
+pub mod user;
+pub mod session;
+pub mod nonce;
+pub mod kyc;
+pub mod tombstone;
+pub mod licensee_record;
+#[cfg(test)]
+pub mod in_memory;
+
+pub use user::*;
+pub use session::*;
+pub use nonce::*;
+pub use kyc::*;
+pub use tombstone::*;
+pub use licensee_record::*;
+#[cfg(test)]
+pub use in_memory::*;
@@ -0,0 +1,73 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use mongodb::{bson::{doc, DateTime}, options::IndexOptions, Collection, Database, IndexModel};
+
+use crate::{models::Nonce, configs::get_collection, utils::CustomError};
+
+/// `NonceRepository` abstracts how the nonces issued for the SIWE login flow are stored.
+#[async_trait]
+pub trait NonceRepository: Send + Sync {
+    /// Stores a newly issued nonce.
+    async fn store_nonce(&self, nonce: &Nonce) -> Result<(), CustomError>;
+
+    /// Consumes the given nonce so that it can't be used again.
+    ///
+    /// Returns an error if the nonce was never issued, has already been used or has expired.
+    async fn consume_nonce(&self, nonce: &str) -> Result<(), CustomError>;
+}
+
+/// `MongoNonceRepository` stores nonces in the `Nonces` collection.
+#[derive(Debug, Clone)]
+pub struct MongoNonceRepository {
+    nonces: Collection<Nonce>,
+}
+
+impl MongoNonceRepository {
+    /// Creates a new `MongoNonceRepository` instance from the given database.
+    pub fn new(db: &Database) -> Self {
+        Self {
+            nonces: get_collection(db, "Nonces"),
+        }
+    }
+
+    /// Creates the indexes required by the `Nonces` collection.
+    ///
+    /// This includes a TTL index on `expires_at` so that expired nonces are automatically purged.
+    pub async fn create_indexes(&self) -> Result<(), CustomError> {
+        let ttl_index = IndexModel::builder()
+            .keys(doc! { "expires_at": 1 })
+            .options(IndexOptions::builder().expire_after(Duration::from_secs(0)).build())
+            .build();
+        let nonce_index = IndexModel::builder()
+            .keys(doc! { "nonce": 1 })
+            .options(IndexOptions::builder().unique(true).build())
+            .build();
+
+        self.nonces.create_indexes([ttl_index, nonce_index], None).await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl NonceRepository for MongoNonceRepository {
+    async fn store_nonce(&self, nonce: &Nonce) -> Result<(), CustomError> {
+        self.nonces.insert_one(nonce, None).await?;
+
+        Ok(())
+    }
+
+    async fn consume_nonce(&self, nonce: &str) -> Result<(), CustomError> {
+        // finding and deleting the nonce in a single atomic operation ensures it can only back a single session.
+        let consumed = self.nonces.find_one_and_delete(
+            doc! { "nonce": nonce, "expires_at": { "$gt": DateTime::now() } },
+            None
+        ).await?;
+
+        match consumed {
+            Some(_) => Ok(()),
+            None => Err(CustomError::Auth("Nonce is invalid, expired or has already been used.".to_string()))
+        }
+    }
+}
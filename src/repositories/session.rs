@@ -0,0 +1,128 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use futures::stream::TryStreamExt;
+use mongodb::{bson::{oid::ObjectId, doc}, options::IndexOptions, Collection, Database, IndexModel};
+
+use crate::{models::Session, configs::get_collection, utils::CustomError};
+
+/// `SessionRepository` abstracts how `Session` instances are stored.
+#[async_trait]
+pub trait SessionRepository: Send + Sync {
+    /// Stores a new session, returning its `ObjectId`.
+    async fn store_session(&self, session: &Session) -> Result<ObjectId, CustomError>;
+
+    /// Gets a session by its `ObjectId`, if it exists.
+    async fn get_session(&self, session_id: ObjectId) -> Result<Option<Session>, CustomError>;
+
+    /// Gets the session whose bearer token hashes to `token_hash` (see `Session::hash_token`), if it exists.
+    async fn get_session_by_token_hash(&self, token_hash: &str) -> Result<Option<Session>, CustomError>;
+
+    /// Gets all of the unexpired sessions tied to the given wallet address.
+    async fn get_active_sessions(&self, wallet_address: &str) -> Result<Vec<Session>, CustomError>;
+
+    /// Gets all of the sessions (including expired ones) tied to the given wallet address.
+    async fn get_sessions(&self, wallet_address: &str) -> Result<Vec<Session>, CustomError>;
+
+    /// Revokes (i.e. deletes) the session with the given ID, as long as it is tied to the given wallet address.
+    /// 
+    /// Returns whether a session was revoked.
+    async fn revoke_session(&self, session_id: ObjectId, wallet_address: &str) -> Result<bool, CustomError>;
+
+    /// Revokes (i.e. deletes) all of the sessions tied to the given wallet address, returning the amount revoked.
+    async fn revoke_all_sessions(&self, wallet_address: &str) -> Result<u64, CustomError>;
+}
+
+/// `MongoSessionRepository` stores sessions in the `Sessions` collection.
+#[derive(Debug, Clone)]
+pub struct MongoSessionRepository {
+    sessions: Collection<Session>,
+}
+
+impl MongoSessionRepository {
+    /// Creates a new `MongoSessionRepository` instance from the given database.
+    pub fn new(db: &Database) -> Self {
+        Self {
+            sessions: get_collection(db, "Sessions"),
+        }
+    }
+
+    /// Creates the indexes required by the `Sessions` collection.
+    /// 
+    /// This includes a TTL index on `expires_at` so that expired sessions are automatically purged.
+    pub async fn create_indexes(&self) -> Result<(), CustomError> {
+        let ttl_index = IndexModel::builder()
+            .keys(doc! { "expires_at": 1 })
+            .options(IndexOptions::builder().expire_after(Duration::from_secs(0)).build())
+            .build();
+        let token_index = IndexModel::builder()
+            .keys(doc! { "token_hash": 1 })
+            .build();
+        let wallet_index = IndexModel::builder()
+            .keys(doc! { "wallet_address": 1, "expiration_date": 1 })
+            .build();
+
+        self.sessions.create_indexes([ttl_index, token_index, wallet_index], None).await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SessionRepository for MongoSessionRepository {
+    async fn store_session(&self, session: &Session) -> Result<ObjectId, CustomError> {
+        let result = self.sessions.insert_one(session, None).await?;
+
+        match result.inserted_id.as_object_id() {
+            Some(id) => Ok(id),
+            None => Err(CustomError::Database("Failed to get inserted ID.".to_string()))
+        }
+    }
+
+    async fn get_session(&self, session_id: ObjectId) -> Result<Option<Session>, CustomError> {
+        Ok(self.sessions.find_one(doc! { "_id": session_id }, None).await?)
+    }
+
+    async fn get_session_by_token_hash(&self, token_hash: &str) -> Result<Option<Session>, CustomError> {
+        // sessions created before tokens were introduced have an empty token hash and must never match.
+        if token_hash.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(self.sessions.find_one(doc! { "token_hash": token_hash }, None).await?)
+    }
+
+    async fn get_active_sessions(&self, wallet_address: &str) -> Result<Vec<Session>, CustomError> {
+        let sessions = self.sessions.find(
+            doc! {
+                "wallet_address": wallet_address.to_lowercase(),
+                "expiration_date": { "$gt": Utc::now().timestamp() }
+            },
+            None
+        ).await?;
+
+        Ok(sessions.try_collect().await?)
+    }
+
+    async fn get_sessions(&self, wallet_address: &str) -> Result<Vec<Session>, CustomError> {
+        let sessions = self.sessions.find(doc! { "wallet_address": wallet_address.to_lowercase() }, None).await?;
+
+        Ok(sessions.try_collect().await?)
+    }
+
+    async fn revoke_session(&self, session_id: ObjectId, wallet_address: &str) -> Result<bool, CustomError> {
+        let result = self.sessions.delete_one(
+            doc! { "_id": session_id, "wallet_address": wallet_address.to_lowercase() },
+            None
+        ).await?;
+
+        Ok(result.deleted_count > 0)
+    }
+
+    async fn revoke_all_sessions(&self, wallet_address: &str) -> Result<u64, CustomError> {
+        let result = self.sessions.delete_many(doc! { "wallet_address": wallet_address.to_lowercase() }, None).await?;
+
+        Ok(result.deleted_count)
+    }
+}
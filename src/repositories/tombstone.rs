@@ -0,0 +1,35 @@
+use async_trait::async_trait;
+use mongodb::{Collection, Database};
+
+use crate::{models::Tombstone, configs::get_collection, utils::CustomError};
+
+/// `TombstoneRepository` abstracts how the `Tombstone` instances of deleted users are stored.
+#[async_trait]
+pub trait TombstoneRepository: Send + Sync {
+    /// Stores a new tombstone.
+    async fn store_tombstone(&self, tombstone: &Tombstone) -> Result<(), CustomError>;
+}
+
+/// `MongoTombstoneRepository` stores tombstones in the `Tombstones` collection.
+#[derive(Debug, Clone)]
+pub struct MongoTombstoneRepository {
+    tombstones: Collection<Tombstone>,
+}
+
+impl MongoTombstoneRepository {
+    /// Creates a new `MongoTombstoneRepository` instance from the given database.
+    pub fn new(db: &Database) -> Self {
+        Self {
+            tombstones: get_collection(db, "Tombstones"),
+        }
+    }
+}
+
+#[async_trait]
+impl TombstoneRepository for MongoTombstoneRepository {
+    async fn store_tombstone(&self, tombstone: &Tombstone) -> Result<(), CustomError> {
+        self.tombstones.insert_one(tombstone, None).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,271 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use futures::stream::TryStreamExt;
+use mongodb::{bson::{oid::ObjectId, doc, Document, Regex}, Collection, Database, IndexModel, options::{Collation, CollationStrength, FindOneAndUpdateOptions, FindOptions, IndexOptions, ReturnDocument}};
+
+use crate::{models::User, configs::get_collection, utils::CustomError};
+
+/// `UserUpdate` contains the fields of a `User` to update; fields that are `None` are left untouched.
+/// 
+/// Fields are expected to have been validated beforehand.
+#[derive(Debug, Clone, Default)]
+pub struct UserUpdate {
+    pub name: Option<String>,
+    pub dob: Option<i64>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub address: Option<String>,
+    pub company: Option<String>,
+    pub kyc_verified: Option<bool>,
+    pub last_kyc_verification: Option<i64>,
+}
+
+impl UserUpdate {
+    /// Checks whether there is nothing to update.
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.dob.is_none()
+            && self.email.is_none()
+            && self.phone.is_none()
+            && self.address.is_none()
+            && self.company.is_none()
+            && self.kyc_verified.is_none()
+            && self.last_kyc_verification.is_none()
+    }
+}
+
+/// `UserSortField` is the field that users can be sorted by when listing them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserSortField {
+    CreatedAt,
+    UpdatedAt,
+}
+
+/// `UserFilter` contains the filters, sorting and paging used when listing users.
+#[derive(Debug, Clone)]
+pub struct UserFilter {
+    /// only return users with the given KYC status
+    pub kyc_verified: Option<bool>,
+    /// only return users whose company name contains this (case-insensitive)
+    pub company: Option<String>,
+    /// only return users created at or after this UNIX timestamp
+    pub created_from: Option<i64>,
+    /// only return users created at or before this UNIX timestamp
+    pub created_to: Option<i64>,
+    /// the field to sort by
+    pub sort_by: UserSortField,
+    /// whether to sort in ascending order
+    pub ascending: bool,
+    /// the amount of users to skip
+    pub skip: u64,
+    /// the maximum amount of users to return
+    pub limit: i64,
+}
+
+/// `UserRepository` abstracts how `User` instances are stored.
+#[async_trait]
+pub trait UserRepository: Send + Sync {
+    /// Stores a new user, returning its `ObjectId`.
+    async fn store_user(&self, user: &User) -> Result<ObjectId, CustomError>;
+
+    /// Gets a user's profile, returning an error if it doesn't exist.
+    async fn get_user(&self, wallet_address: &str) -> Result<User, CustomError>;
+
+    /// Checks whether a user with the specified wallet address exists.
+    async fn user_exists(&self, wallet_address: &str) -> Result<bool, CustomError>;
+
+    /// Creates a new user with the given wallet address if it doesn't exist yet, otherwise bumps its `updated_at`.
+    /// 
+    /// Returns the `ObjectId` of the (possibly newly created) user.
+    async fn upsert_user(&self, wallet_address: &str) -> Result<ObjectId, CustomError>;
+
+    /// Updates the given fields of a user's profile and bumps its `updated_at`, returning the updated user.
+    async fn update_user(&self, wallet_address: &str, update: UserUpdate) -> Result<User, CustomError>;
+
+    /// Gets a page of users matching `filter`, along with the total amount of users matching it.
+    async fn list_users(&self, filter: UserFilter) -> Result<(Vec<User>, u64), CustomError>;
+
+    /// Deletes a user, returning whether a user was deleted.
+    async fn delete_user(&self, wallet_address: &str) -> Result<bool, CustomError>;
+}
+
+/// `MongoUserRepository` stores users in the `Users` collection.
+#[derive(Debug, Clone)]
+pub struct MongoUserRepository {
+    users: Collection<User>,
+}
+
+impl MongoUserRepository {
+    /// Creates a new `MongoUserRepository` instance from the given database.
+    pub fn new(db: &Database) -> Self {
+        Self {
+            users: get_collection(db, "Users"),
+        }
+    }
+
+    /// Creates the indexes required by the `Users` collection.
+    /// 
+    /// This includes a unique index on `wallet_address` so that concurrent sign-ups can't create duplicate users.
+    /// Uniqueness is enforced by a second, case-insensitive index (collation strength 2), so it doesn't depend on every write path lowercasing;
+    /// lookups (which use the default collation) are served by the first one.
+    pub async fn create_indexes(&self) -> Result<(), CustomError> {
+        let wallet_index = IndexModel::builder()
+            .keys(doc! { "wallet_address": 1 })
+            .options(IndexOptions::builder().unique(true).build())
+            .build();
+        let wallet_ci_index = IndexModel::builder()
+            .keys(doc! { "wallet_address": 1 })
+            .options(
+                IndexOptions::builder()
+                    .name("wallet_address_ci_unique".to_string())
+                    .unique(true)
+                    .collation(Collation::builder().locale("en".to_string()).strength(CollationStrength::Secondary).build())
+                    .build()
+            )
+            .build();
+        let created_at_index = IndexModel::builder()
+            .keys(doc! { "created_at": -1 })
+            .build();
+
+        self.users.create_indexes([wallet_index, wallet_ci_index, created_at_index], None).await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl UserRepository for MongoUserRepository {
+    async fn store_user(&self, user: &User) -> Result<ObjectId, CustomError> {
+        let result = self.users.insert_one(user, None).await?;
+
+        match result.inserted_id.as_object_id() {
+            Some(id) => Ok(id),
+            None => Err(CustomError::Database("Failed to get inserted ID.".to_string()))
+        }
+    }
+
+    async fn get_user(&self, wallet_address: &str) -> Result<User, CustomError> {
+        // case-friendly search
+        let user = self.users.find_one(doc! { "wallet_address": wallet_address.to_lowercase() }, None).await?;
+
+        match user {
+            Some(user) => Ok(user),
+            None => Err(CustomError::NotFound("User not found.".to_string()))
+        }
+    }
+
+    async fn user_exists(&self, wallet_address: &str) -> Result<bool, CustomError> {
+        let user = self.users.find_one(doc! { "wallet_address": wallet_address.to_lowercase() }, None).await?;
+
+        Ok(user.is_some())
+    }
+
+    async fn upsert_user(&self, wallet_address: &str) -> Result<ObjectId, CustomError> {
+        let now = Utc::now().timestamp();
+
+        let options = FindOneAndUpdateOptions::builder()
+            .upsert(true)
+            .return_document(ReturnDocument::After)
+            .build();
+
+        let user = self.users.find_one_and_update(
+            doc! { "wallet_address": wallet_address.to_lowercase() },
+            doc! {
+                "$set": { "updated_at": now },
+                "$setOnInsert": {
+                    "created_at": now,
+                    "kyc_verified": false,
+                    "last_kyc_verification": 0_i64
+                }
+            },
+            options
+        ).await?;
+
+        match user.and_then(|user| user._id) {
+            Some(id) => Ok(id),
+            None => Err(CustomError::Database("Failed to upsert user.".to_string()))
+        }
+    }
+
+    async fn update_user(&self, wallet_address: &str, update: UserUpdate) -> Result<User, CustomError> {
+        let mut fields = doc! { "updated_at": Utc::now().timestamp() };
+
+        if let Some(name) = update.name { fields.insert("name", name); }
+        if let Some(dob) = update.dob { fields.insert("dob", dob); }
+        if let Some(email) = update.email { fields.insert("email", email); }
+        if let Some(phone) = update.phone { fields.insert("phone", phone); }
+        if let Some(address) = update.address { fields.insert("address", address); }
+        if let Some(company) = update.company { fields.insert("company", company); }
+        if let Some(kyc_verified) = update.kyc_verified { fields.insert("kyc_verified", kyc_verified); }
+        if let Some(last_kyc_verification) = update.last_kyc_verification { fields.insert("last_kyc_verification", last_kyc_verification); }
+
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+
+        let user = self.users.find_one_and_update(
+            doc! { "wallet_address": wallet_address.to_lowercase() },
+            doc! { "$set": fields },
+            options
+        ).await?;
+
+        match user {
+            Some(user) => Ok(user),
+            None => Err(CustomError::NotFound("User not found.".to_string()))
+        }
+    }
+
+    async fn list_users(&self, filter: UserFilter) -> Result<(Vec<User>, u64), CustomError> {
+        let mut query = doc! {};
+
+        if let Some(kyc_verified) = filter.kyc_verified {
+            query.insert("kyc_verified", kyc_verified);
+        }
+
+        if let Some(company) = &filter.company {
+            // escapes regex metacharacters so that the company name is matched literally.
+            let pattern: String = company.chars().map(|c| match c.is_alphanumeric() || c == ' ' {
+                true => c.to_string(),
+                false => format!("\\{}", c)
+            }).collect();
+
+            query.insert("company", Regex { pattern, options: "i".to_string() });
+        }
+
+        let mut created_at = Document::new();
+
+        if let Some(created_from) = filter.created_from {
+            created_at.insert("$gte", created_from);
+        }
+
+        if let Some(created_to) = filter.created_to {
+            created_at.insert("$lte", created_to);
+        }
+
+        if !created_at.is_empty() {
+            query.insert("created_at", created_at);
+        }
+
+        let sort_by = match filter.sort_by {
+            UserSortField::CreatedAt => "created_at",
+            UserSortField::UpdatedAt => "updated_at",
+        };
+
+        let total_items = self.users.count_documents(query.clone(), None).await?;
+
+        let options = FindOptions::builder()
+            .sort(doc! { sort_by: if filter.ascending { 1 } else { -1 } })
+            .skip(filter.skip)
+            .limit(filter.limit)
+            .build();
+        let users = self.users.find(query, options).await?;
+
+        Ok((users.try_collect().await?, total_items))
+    }
+
+    async fn delete_user(&self, wallet_address: &str) -> Result<bool, CustomError> {
+        let result = self.users.delete_one(doc! { "wallet_address": wallet_address.to_lowercase() }, None).await?;
+
+        Ok(result.deleted_count > 0)
+    }
+}
@@ -0,0 +1,245 @@
+use axum::{Router, routing::{get, post, delete}, extract::State};
+use serde_json::json;
+
+use crate::{models::{ApiResponse, LoginUser, RefreshAccessToken}, api::{generate_nonce, login, login_access_token, refresh_access_token, get_sessions, revoke_session, logout}, utils::{AuthenticatedUser, Json, Path}, configs::AppState};
+
+pub fn auth_routes() -> Router<AppState> {
+    Router::new()
+        .route("/nonce", get(nonce_route))
+        .route("/login", post(login_route))
+        .route("/refresh", post(refresh_route))
+        .route("/logout", post(logout_route))
+        .route("/sessions", get(get_sessions_route))
+        .route("/sessions/:id", delete(revoke_session_route))
+}
+
+async fn nonce_route(State(state): State<AppState>) -> ApiResponse {
+    match generate_nonce(state.nonces.as_ref()).await {
+        Ok(nonce) => ApiResponse::success(
+            "Successfully generated nonce.",
+            Some(json!({
+                "nonce": nonce
+            }))
+        ),
+
+        Err(e) => ApiResponse::from_error("Failed to generate nonce.", e)
+    }
+}
+
+async fn login_route(State(state): State<AppState>, Json(payload): Json<LoginUser>) -> ApiResponse {
+    let session = login(state.nonces.as_ref(), state.users.as_ref(), state.sessions.as_ref(), payload).await;
+
+    // in token mode, a signed access token is issued alongside the session token (which then acts as a refresh token).
+    let session = session.and_then(|(session, token)| {
+        let access_token = login_access_token(&session)?;
+        Ok((session, token, access_token))
+    });
+
+    match session {
+        Ok((session, token, access_token)) => ApiResponse::success(
+            "Successfully logged in.",
+            Some(json!({
+                "sessionId": session._id,
+                "token": token,
+                "accessToken": access_token
+            }))
+        ),
+
+        Err(e) => ApiResponse::from_error("Failed to log in.", e)
+    }
+}
+
+async fn refresh_route(State(state): State<AppState>, Json(payload): Json<RefreshAccessToken>) -> ApiResponse {
+    match refresh_access_token(state.sessions.as_ref(), &payload.refresh_token).await {
+        Ok(access_token) => ApiResponse::success(
+            "Successfully refreshed access token.",
+            Some(json!({
+                "accessToken": access_token
+            }))
+        ),
+
+        Err(e) => ApiResponse::from_error("Failed to refresh access token.", e)
+    }
+}
+
+async fn logout_route(State(state): State<AppState>, auth: AuthenticatedUser) -> ApiResponse {
+    match logout(state.sessions.as_ref(), &auth).await {
+        Ok(_) => ApiResponse::success("Successfully logged out.", None),
+        Err(e) => ApiResponse::from_error("Failed to log out.", e)
+    }
+}
+
+async fn get_sessions_route(State(state): State<AppState>, auth: AuthenticatedUser) -> ApiResponse {
+    match get_sessions(state.sessions.as_ref(), &auth).await {
+        Ok(sessions) => ApiResponse::success("Successfully retrieved sessions.", Some(json!(sessions))),
+        Err(e) => ApiResponse::from_error("Failed to retrieve sessions.", e)
+    }
+}
+
+async fn revoke_session_route(State(state): State<AppState>, auth: AuthenticatedUser, Path(session_id): Path<String>) -> ApiResponse {
+    match revoke_session(state.sessions.as_ref(), &auth, session_id).await {
+        Ok(_) => ApiResponse::success("Successfully revoked session.", None),
+        Err(e) => ApiResponse::from_error("Failed to revoke session.", e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{extract::{State, FromRequestParts}, http::StatusCode};
+    use ethers::{core::rand::thread_rng, signers::{LocalWallet, Signer}};
+
+    use chrono::Utc;
+
+    use crate::{
+        models::{Session, LoginUser},
+        configs::{AppState, MAX_SESSION_TTL},
+        utils::{AuthenticatedUser, CustomError, Json, Path, testing::{init_test_env, issued_login, signed_login, sign_login, sign_up_with_token, bearer_parts}}
+    };
+
+    use super::{nonce_route, login_route, logout_route, get_sessions_route, revoke_session_route};
+
+    #[tokio::test]
+    async fn nonce_route_issues_a_nonce() {
+        let response = nonce_route(State(AppState::in_memory())).await;
+
+        assert_eq!(response.status, StatusCode::OK);
+        assert_eq!(response.data.unwrap()["nonce"].as_str().unwrap().len(), 17);
+    }
+
+    #[tokio::test]
+    async fn login_route_creates_the_user_and_a_session() {
+        init_test_env();
+
+        let state = AppState::in_memory();
+        let wallet = LocalWallet::new(&mut thread_rng());
+        let login = issued_login(&state, &wallet).await;
+
+        let response = login_route(State(state.clone()), Json(login)).await;
+        assert_eq!(response.status, StatusCode::OK);
+
+        // only the token's hash is stored, so the session must be found through it.
+        let token = response.data.unwrap()["token"].as_str().unwrap().to_string();
+        let session = state.sessions.get_session_by_token_hash(&Session::hash_token(&token)).await.unwrap().unwrap();
+        let wallet_address = format!("{:?}", wallet.address());
+
+        assert_eq!(session.wallet_address, wallet_address);
+        assert_ne!(session.token_hash, token);
+        assert!(state.users.user_exists(&wallet_address).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn login_route_caps_the_session_lifetime() {
+        init_test_env();
+
+        let state = AppState::in_memory();
+        let wallet = LocalWallet::new(&mut thread_rng());
+        let login = issued_login(&state, &wallet).await;
+        let login = sign_login(&wallet, LoginUser { expiration_time: "2099-01-01T00:00:00Z".to_string(), ..login }).await;
+
+        let response = login_route(State(state.clone()), Json(login)).await;
+        assert_eq!(response.status, StatusCode::OK);
+
+        let token = response.data.unwrap()["token"].as_str().unwrap().to_string();
+        let session = state.sessions.get_session_by_token_hash(&Session::hash_token(&token)).await.unwrap().unwrap();
+
+        assert!(session.expiration_date <= Utc::now().timestamp() + MAX_SESSION_TTL);
+        assert_eq!(session.expires_at.unwrap().timestamp_millis(), session.expiration_date * 1000);
+    }
+
+    #[tokio::test]
+    async fn login_route_rejects_a_reused_nonce() {
+        init_test_env();
+
+        let state = AppState::in_memory();
+        let wallet = LocalWallet::new(&mut thread_rng());
+        let login = issued_login(&state, &wallet).await;
+        let replay = signed_login(&wallet, login.nonce.clone()).await;
+
+        assert_eq!(login_route(State(state.clone()), Json(login)).await.status, StatusCode::OK);
+        assert_eq!(login_route(State(state), Json(replay)).await.status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn login_route_rejects_a_nonce_that_was_never_issued() {
+        init_test_env();
+
+        let wallet = LocalWallet::new(&mut thread_rng());
+        let login = signed_login(&wallet, "notarealnonce1234".to_string()).await;
+
+        assert_eq!(login_route(State(AppState::in_memory()), Json(login)).await.status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn login_route_rejects_a_message_that_differs_from_the_signed_one() {
+        init_test_env();
+
+        let state = AppState::in_memory();
+        let wallet = LocalWallet::new(&mut thread_rng());
+        let mut login = issued_login(&state, &wallet).await;
+        login.chain_id = 1;
+
+        assert_eq!(login_route(State(state.clone()), Json(login)).await.status, StatusCode::UNAUTHORIZED);
+        assert!(!state.users.user_exists(&format!("{:?}", wallet.address())).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn get_sessions_route_lists_only_your_own_sessions() {
+        let state = AppState::in_memory();
+        let (wallet, auth, _) = sign_up_with_token(&state).await;
+        sign_up_with_token(&state).await;
+
+        // a second login adds a second session for the same wallet.
+        let login = issued_login(&state, &wallet).await;
+        assert_eq!(login_route(State(state.clone()), Json(login)).await.status, StatusCode::OK);
+
+        let response = get_sessions_route(State(state), auth.clone()).await;
+        assert_eq!(response.status, StatusCode::OK);
+
+        let sessions = response.data.unwrap().as_array().unwrap().clone();
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions.iter().filter(|session| session["current"] == true).count(), 1);
+        assert!(sessions.iter().any(|session| session["id"]["$oid"] == auth.session_id.to_hex()));
+    }
+
+    #[tokio::test]
+    async fn revoke_session_route_forbids_revoking_another_users_session() {
+        let state = AppState::in_memory();
+        let (_, auth, _) = sign_up_with_token(&state).await;
+        let (_, other, other_token) = sign_up_with_token(&state).await;
+
+        let response = revoke_session_route(State(state.clone()), auth, Path(other.session_id.to_hex())).await;
+        assert_eq!(response.status, StatusCode::FORBIDDEN);
+
+        assert!(AuthenticatedUser::from_request_parts(&mut bearer_parts(&other_token), &state).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn revoke_session_route_revokes_your_own_session() {
+        let state = AppState::in_memory();
+        let (wallet, auth, _) = sign_up_with_token(&state).await;
+
+        let login = issued_login(&state, &wallet).await;
+        let response = login_route(State(state.clone()), Json(login)).await;
+        let data = response.data.unwrap();
+        let session_id = data["sessionId"]["$oid"].as_str().unwrap().to_string();
+        let token = data["token"].as_str().unwrap().to_string();
+
+        let response = revoke_session_route(State(state.clone()), auth.clone(), Path(session_id.clone())).await;
+        assert_eq!(response.status, StatusCode::OK);
+        assert!(AuthenticatedUser::from_request_parts(&mut bearer_parts(&token), &state).await.is_err());
+
+        let response = revoke_session_route(State(state), auth, Path(session_id)).await;
+        assert_eq!(response.status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn logout_route_stops_the_token_from_authenticating() {
+        let state = AppState::in_memory();
+        let (_, _, token) = sign_up_with_token(&state).await;
+
+        let auth = AuthenticatedUser::from_request_parts(&mut bearer_parts(&token), &state).await.unwrap();
+        assert_eq!(logout_route(State(state.clone()), auth).await.status, StatusCode::OK);
+
+        assert!(matches!(AuthenticatedUser::from_request_parts(&mut bearer_parts(&token), &state).await, Err(CustomError::Auth(_))));
+    }
+}
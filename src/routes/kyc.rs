@@ -0,0 +1,163 @@
+use axum::{Router, routing::{get, post}, extract::State};
+use serde_json::json;
+
+use crate::{
+    models::{ApiResponse, SubmitKyc, ReviewKyc, KycStatus},
+    api::{submit_kyc, review_kyc, get_kyc_status},
+    utils::{AuthenticatedUser, RequireOwner, Json, Path},
+    configs::{KYC_EXPIRY_DAYS, AppState}
+};
+
+pub fn kyc_routes() -> Router<AppState> {
+    Router::new()
+        .route("/submit", post(submit_kyc_route))
+        .route("/me", get(get_my_kyc_route))
+        .route("/pending", get(get_pending_kyc_route))
+        .route("/:id/review", post(review_kyc_route))
+}
+
+async fn submit_kyc_route(State(state): State<AppState>, auth: AuthenticatedUser, Json(payload): Json<SubmitKyc>) -> ApiResponse {
+    match submit_kyc(state.kyc_submissions.as_ref(), state.users.as_ref(), auth.wallet_address, payload.documents).await {
+        Ok(submission_id) => ApiResponse::success(
+            "Successfully submitted KYC documents.",
+            Some(json!({
+                "submissionId": submission_id
+            }))
+        ),
+
+        Err(e) => ApiResponse::from_error("Failed to submit KYC documents.", e)
+    }
+}
+
+async fn get_my_kyc_route(State(state): State<AppState>, auth: AuthenticatedUser) -> ApiResponse {
+    match get_kyc_status(state.kyc_submissions.as_ref(), state.users.as_ref(), auth.wallet_address).await {
+        Ok((user, submissions)) => ApiResponse::success(
+            "Successfully retrieved KYC status.",
+            Some(json!({
+                "kycVerified": user.kyc_verified,
+                "kycValid": user.is_kyc_valid(),
+                "lastKycVerification": user.last_kyc_verification,
+                "kycExpiresAt": match user.kyc_verified {
+                    true => Some(user.last_kyc_verification + *KYC_EXPIRY_DAYS * 86400),
+                    false => None
+                },
+                "submissions": submissions
+            }))
+        ),
+
+        Err(e) => ApiResponse::from_error("Failed to retrieve KYC status.", e)
+    }
+}
+
+async fn get_pending_kyc_route(State(state): State<AppState>, _owner: RequireOwner) -> ApiResponse {
+    match state.kyc_submissions.get_submissions_by_status(KycStatus::Pending).await {
+        Ok(submissions) => ApiResponse::success("Successfully retrieved pending KYC submissions.", Some(json!(submissions))),
+        Err(e) => ApiResponse::from_error("Failed to retrieve pending KYC submissions.", e)
+    }
+}
+
+async fn review_kyc_route(
+    State(state): State<AppState>,
+    RequireOwner(owner): RequireOwner,
+    Path(submission_id): Path<String>,
+    Json(payload): Json<ReviewKyc>
+) -> ApiResponse {
+    match review_kyc(state.kyc_submissions.as_ref(), state.users.as_ref(), submission_id, owner.wallet_address, payload.approved, payload.reason).await {
+        Ok(submission) => ApiResponse::success("Successfully reviewed KYC submission.", Some(json!(submission))),
+        Err(e) => ApiResponse::from_error("Failed to review KYC submission.", e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{extract::State, http::StatusCode};
+
+    use crate::{
+        models::{SubmitKyc, ReviewKyc, KycDocument, KycStatus},
+        configs::AppState,
+        utils::{RequireOwner, Json, Path, testing::sign_up}
+    };
+
+    use super::{submit_kyc_route, review_kyc_route};
+
+    fn submission() -> SubmitKyc {
+        SubmitKyc {
+            documents: vec![KycDocument {
+                document_type: "passport".to_string(),
+                file_name: "passport.pdf".to_string(),
+                file_hash: "abc123".to_string(),
+                url: None,
+            }],
+        }
+    }
+
+    #[tokio::test]
+    async fn submit_kyc_route_rejects_a_second_pending_submission() {
+        let state = AppState::in_memory();
+        let (_, auth) = sign_up(&state).await;
+
+        let first = submit_kyc_route(State(state.clone()), auth.clone(), Json(submission())).await;
+        let second = submit_kyc_route(State(state), auth, Json(submission())).await;
+
+        assert_eq!(first.status, StatusCode::OK);
+        assert_eq!(second.status, StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    async fn submit_kyc_route_requires_a_document() {
+        let state = AppState::in_memory();
+        let (_, auth) = sign_up(&state).await;
+
+        let response = submit_kyc_route(State(state), auth, Json(SubmitKyc { documents: Vec::new() })).await;
+
+        assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn review_kyc_route_approves_a_submission() {
+        let state = AppState::in_memory();
+        let (_, auth) = sign_up(&state).await;
+        let (_, owner) = sign_up(&state).await;
+
+        let submitted = submit_kyc_route(State(state.clone()), auth.clone(), Json(submission())).await;
+        let submission_id = submitted.data.unwrap()["submissionId"]["$oid"].as_str().unwrap().to_string();
+
+        let review = ReviewKyc { approved: true, reason: None };
+        let response = review_kyc_route(State(state.clone()), RequireOwner(owner), Path(submission_id), Json(review)).await;
+        assert_eq!(response.status, StatusCode::OK);
+
+        let user = state.users.get_user(&auth.wallet_address).await.unwrap();
+        assert!(user.kyc_verified);
+        assert!(state.kyc_submissions.get_submissions_by_status(KycStatus::Pending).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn review_kyc_route_requires_a_reason_when_rejecting() {
+        let state = AppState::in_memory();
+        let (_, auth) = sign_up(&state).await;
+        let (_, owner) = sign_up(&state).await;
+
+        let submitted = submit_kyc_route(State(state.clone()), auth, Json(submission())).await;
+        let submission_id = submitted.data.unwrap()["submissionId"]["$oid"].as_str().unwrap().to_string();
+
+        let review = ReviewKyc { approved: false, reason: Some(" ".to_string()) };
+        let response = review_kyc_route(State(state), RequireOwner(owner), Path(submission_id), Json(review)).await;
+
+        assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn review_kyc_route_rejects_reviewing_an_owners_own_submission() {
+        let state = AppState::in_memory();
+        let (_, owner) = sign_up(&state).await;
+
+        let submitted = submit_kyc_route(State(state.clone()), owner.clone(), Json(submission())).await;
+        let submission_id = submitted.data.unwrap()["submissionId"]["$oid"].as_str().unwrap().to_string();
+
+        let review = ReviewKyc { approved: true, reason: None };
+        let response = review_kyc_route(State(state.clone()), RequireOwner(owner.clone()), Path(submission_id), Json(review)).await;
+
+        assert_eq!(response.status, StatusCode::FORBIDDEN);
+        assert!(!state.users.get_user(&owner.wallet_address).await.unwrap().kyc_verified);
+    }
+}
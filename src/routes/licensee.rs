@@ -0,0 +1,158 @@
+use axum::{Router, routing::{get, post}, extract::State};
+use log::info;
+use serde_json::json;
+
+use crate::{
+    models::{ApiResponse, Licensee, RegisterLicensee, LicenseeBatch, UpdateLicenseeBatch, LicenseeBatchOutcome, LicenseeBatchStatus},
+    api::{register_licensee_params, approve_accounts, update_accounts, remove_accounts},
+    utils::{AuthenticatedUser, RequireOwner, RequireMainOwner, CustomError, Json, Path},
+    configs::AppState
+};
+
+pub fn licensee_routes() -> Router<AppState> {
+    Router::new()
+        .route("/:address", get(get_licensee_route))
+        .route("/:address/status", get(get_licensee_status_route))
+        .route("/register-params", post(register_params_route))
+        .route("/approve", post(approve_accounts_route))
+        .route("/update", post(update_accounts_route))
+        .route("/remove", post(remove_accounts_route))
+}
+
+async fn get_licensee_route(State(state): State<AppState>, auth: AuthenticatedUser, Path(address): Path<String>) -> ApiResponse {
+    // licensee data contains personal information, so users may only read their own account, unless they are an owner.
+    let licensee = match auth.can_access(&address).await {
+        Ok(true) => Licensee::get_account_data(state.licensee_records.as_ref(), address).await
+            .and_then(|licensee| licensee.ok_or(CustomError::NotFound("Licensee not found.".to_string()))),
+        Ok(false) => Err(CustomError::Forbidden("Not allowed to access this licensee.".to_string())),
+        Err(e) => Err(e)
+    };
+
+    match licensee {
+        Ok(licensee) => ApiResponse::success(
+            "Successfully retrieved licensee.",
+            Some(json!({
+                "status": licensee.status(),
+                "licensee": licensee
+            }))
+        ),
+
+        Err(e) => ApiResponse::from_error("Failed to retrieve licensee.", e)
+    }
+}
+
+async fn get_licensee_status_route(auth: AuthenticatedUser, Path(address): Path<String>) -> ApiResponse {
+    let status = match auth.can_access(&address).await {
+        Ok(true) => Licensee::get_account_status(address).await,
+        Ok(false) => Err(CustomError::Forbidden("Not allowed to access this licensee.".to_string())),
+        Err(e) => Err(e)
+    };
+
+    match status {
+        Ok(status) => ApiResponse::success(
+            "Successfully retrieved licensee status.",
+            Some(json!({
+                "status": status
+            }))
+        ),
+
+        Err(e) => ApiResponse::from_error("Failed to retrieve licensee status.", e)
+    }
+}
+
+async fn register_params_route(State(state): State<AppState>, auth: AuthenticatedUser, Json(payload): Json<RegisterLicensee>) -> ApiResponse {
+    match register_licensee_params(state.licensee_records.as_ref(), auth.wallet_address, payload).await {
+        Ok(licensee_raw) => ApiResponse::success(
+            "Successfully encoded licensee registration data.",
+            Some(json!({
+                "data": licensee_raw.data
+            }))
+        ),
+
+        Err(e) => ApiResponse::from_error("Failed to encode licensee registration data.", e)
+    }
+}
+
+async fn approve_accounts_route(RequireOwner(owner): RequireOwner, Json(payload): Json<LicenseeBatch>) -> ApiResponse {
+    info!("{} is approving {} licensee(s)", owner.wallet_address, payload.licensees.len());
+
+    match approve_accounts(payload.licensees).await {
+        Ok(outcomes) => batch_response("approved", outcomes),
+        Err(e) => ApiResponse::from_error("Failed to approve licensees.", e)
+    }
+}
+
+async fn update_accounts_route(State(state): State<AppState>, RequireOwner(owner): RequireOwner, Json(payload): Json<UpdateLicenseeBatch>) -> ApiResponse {
+    info!("{} is updating {} licensee(s)", owner.wallet_address, payload.accounts.len());
+
+    match update_accounts(state.licensee_records.as_ref(), payload.accounts).await {
+        Ok(outcomes) => batch_response("updated", outcomes),
+        Err(e) => ApiResponse::from_error("Failed to update licensees.", e)
+    }
+}
+
+/// removing licensees can't be undone, so unlike approving and updating, it is restricted to the main owner.
+async fn remove_accounts_route(State(state): State<AppState>, RequireMainOwner(owner): RequireMainOwner, Json(payload): Json<LicenseeBatch>) -> ApiResponse {
+    info!("{} is removing {} licensee(s)", owner.wallet_address, payload.licensees.len());
+
+    match remove_accounts(state.licensee_records.as_ref(), payload.licensees).await {
+        Ok(outcomes) => batch_response("removed", outcomes),
+        Err(e) => ApiResponse::from_error("Failed to remove licensees.", e)
+    }
+}
+
+/// Builds the response of a batch endpoint; the outcome of every batch is reported, even if some of them failed or weren't mined in time.
+fn batch_response(action: &str, outcomes: Vec<LicenseeBatchOutcome>) -> ApiResponse {
+    let count = |status: LicenseeBatchStatus| outcomes.iter().filter(|outcome| outcome.status == status).count();
+    let (confirmed, submitted) = (count(LicenseeBatchStatus::Confirmed), count(LicenseeBatchStatus::Submitted));
+
+    let message = match submitted {
+        0 => format!("Successfully {} licensees in {} of {} batch(es).", action, confirmed, outcomes.len()),
+        _ => format!("Successfully {} licensees in {} of {} batch(es); {} batch(es) are not mined yet.", action, confirmed, outcomes.len(), submitted)
+    };
+
+    ApiResponse::success(
+        &message,
+        Some(json!({
+            "batches": outcomes
+        }))
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::extract::FromRequestParts;
+
+    use crate::{configs::AppState, utils::{RequireOwner, RequireMainOwner, Roles, CustomError, cache_roles, testing::{sign_up_with_token, bearer_parts}}};
+
+    #[tokio::test]
+    async fn only_the_main_owner_can_remove_licensees() {
+        let state = AppState::in_memory();
+        let (_, owner, owner_token) = sign_up_with_token(&state).await;
+        let (_, main_owner, main_owner_token) = sign_up_with_token(&state).await;
+
+        cache_roles(&owner.wallet_address, Roles { is_owner: true, is_main_owner: false });
+        cache_roles(&main_owner.wallet_address, Roles { is_owner: false, is_main_owner: true });
+
+        // regular owners may approve and update licensees, but not remove them.
+        assert!(RequireOwner::from_request_parts(&mut bearer_parts(&owner_token), &state).await.is_ok());
+        assert!(matches!(
+            RequireMainOwner::from_request_parts(&mut bearer_parts(&owner_token), &state).await,
+            Err(CustomError::Forbidden(_))
+        ));
+
+        assert!(RequireOwner::from_request_parts(&mut bearer_parts(&main_owner_token), &state).await.is_ok());
+        assert!(RequireMainOwner::from_request_parts(&mut bearer_parts(&main_owner_token), &state).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn users_without_a_role_cannot_manage_licensees() {
+        let state = AppState::in_memory();
+        let (_, user, token) = sign_up_with_token(&state).await;
+
+        cache_roles(&user.wallet_address, Roles::default());
+
+        assert!(matches!(RequireOwner::from_request_parts(&mut bearer_parts(&token), &state).await, Err(CustomError::Forbidden(_))));
+        assert!(matches!(RequireMainOwner::from_request_parts(&mut bearer_parts(&token), &state).await, Err(CustomError::Forbidden(_))));
+    }
+}
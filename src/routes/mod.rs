@@ -1,3 +1,9 @@
 pub mod user;
+pub mod auth;
+pub mod kyc;
+pub mod licensee;
 
-pub use user::*;
\ No newline at end of file
+pub use user::*;
+pub use auth::*;
+pub use kyc::*;
+pub use licensee::*;
\ No newline at end of file
@@ -1,117 +1,285 @@
-use axum::{response::{IntoResponse, Response}, http::StatusCode, Router, routing::{get, post}, extract::{Json, Request, Path}};
+use axum::{Router, routing::{get, post, patch}, extract::State};
 use serde_json::json;
 use axum_macros::debug_handler;
 use log::info;
 
-use crate::{models::{User, ApiResponse, CreateUser}, api::create_user};
+use crate::{models::{ApiResponse, CreateUser, UpdateUser, ListUsersQuery}, api::{create_user, login_access_token, update_user, list_users, delete_user, export_user_data}, utils::{AuthenticatedUser, RequireOwner, CustomError, Json, Path, Query}, configs::AppState};
 
-pub fn user_routes() -> Router {
+pub fn user_routes() -> Router<AppState> {
     Router::new()
+        .route("/", get(list_users_route))
         .route("/hello-world", get(hello_world))
         .route("/create-user", post(create_user_route))
         .route("/get-user/:wallet_address", get(get_user_route))
+        .route("/me", patch(update_me_route).delete(delete_me_route))
+        .route("/me/export", get(export_me_route))
 }
 
-async fn hello_world() -> impl IntoResponse {
-    (StatusCode::OK, "Hello, World!")
+async fn hello_world() -> ApiResponse {
+    ApiResponse::success("Hello, World!", None)
 }
 
 #[debug_handler]
-async fn create_user_route(Json(payload): Json<CreateUser>) -> impl IntoResponse {
-    let mut status_code = StatusCode::OK;
-
+async fn create_user_route(State(state): State<AppState>, Json(payload): Json<CreateUser>) -> ApiResponse {
     info!("Payload: {:?}", payload);
 
-    let object_ids = create_user(
-        payload.wallet_address,
-        payload.expiration_date,
-        payload.chain_id,
-        payload.domain,
-        payload.nonce,
-        payload.signature,
-        payload.payload,
-        payload.profile_id,
-        payload.uri,
-        payload.version
-    ).await;
+    let user = create_user(state.nonces.as_ref(), state.users.as_ref(), state.sessions.as_ref(), payload).await;
 
-    let api_response = match object_ids {
-        Ok(ids) => {
-            let api_response = ApiResponse {
-                status: StatusCode::OK,
-                message: "Successfully created user.".to_string(),
-                data: Some(json!({
-                    "userId": &ids[0],
-                    "sessionId": &ids[1]
-                })),
-                error: None,
-                pagination: None,
-                version: 1
-            };
-
-            api_response
-        },
-
-        Err(e) => {
-            status_code = StatusCode::BAD_REQUEST;
-
-            let api_response= ApiResponse {
-                status: StatusCode::BAD_REQUEST,
-                message: "Failed to create user.".to_string(),
-                data: None,
-                error: Some(e.to_string()),
-                pagination: None,
-                version: 1
-            };
-
-            api_response
-        }
-    };
+    let user = user.and_then(|(user_id, session, token)| {
+        let access_token = login_access_token(&session)?;
+        Ok((user_id, session, token, access_token))
+    });
 
-    (
-        status_code,
-        serde_json::to_string_pretty(&api_response).unwrap()
-    )
+    match user {
+        Ok((user_id, session, token, access_token)) => ApiResponse::success(
+            "Successfully created user.",
+            Some(json!({
+                "userId": user_id,
+                "sessionId": session._id,
+                "token": token,
+                "accessToken": access_token
+            }))
+        ),
+
+        Err(e) => ApiResponse::from_error("Failed to create user.", e)
+    }
 }
 
-async fn get_user_route(Path(wallet_address): Path<String>) -> impl IntoResponse {
-    let mut status_code = StatusCode::OK;
-
-    let user = User::get_user(wallet_address).await;
-
-    let api_response = match user {
-        Ok(user) => {
-            let api_response = ApiResponse {
-                status: StatusCode::OK,
-                message: "Successfully retrieved user.".to_string(),
-                data: Some(json!(user)),
-                error: None,
-                pagination: None,
-                version: 1
-            };
-
-            api_response
-        },
-
-        Err(e) => {
-            status_code = StatusCode::BAD_REQUEST;
-
-            let api_response= ApiResponse {
-                status: StatusCode::BAD_REQUEST,
-                message: "Failed to retrieve user.".to_string(),
-                data: None,
-                error: Some(e.to_string()),
-                pagination: None,
-                version: 1
-            };
-
-            api_response
-        }
+async fn get_user_route(State(state): State<AppState>, auth: AuthenticatedUser, Path(wallet_address): Path<String>) -> ApiResponse {
+    // users may only read their own profile, unless they are an owner.
+    let user = match auth.can_access(&wallet_address).await {
+        Ok(true) => state.users.get_user(&wallet_address).await,
+        Ok(false) => Err(CustomError::Forbidden("Not allowed to access this user.".to_string())),
+        Err(e) => Err(e)
     };
 
-    (
-        status_code,
-        serde_json::to_string_pretty(&api_response).unwrap()
-    )
+    match user {
+        Ok(user) => ApiResponse::success("Successfully retrieved user.", Some(json!(user))),
+        Err(e) => ApiResponse::from_error("Failed to retrieve user.", e)
+    }
+}
+
+async fn update_me_route(State(state): State<AppState>, auth: AuthenticatedUser, Json(payload): Json<UpdateUser>) -> ApiResponse {
+    match update_user(state.users.as_ref(), auth.wallet_address, payload).await {
+        Ok(user) => ApiResponse::success("Successfully updated user.", Some(json!(user))),
+        Err(e) => ApiResponse::from_error("Failed to update user.", e)
+    }
 }
 
+async fn list_users_route(State(state): State<AppState>, _owner: RequireOwner, Query(query): Query<ListUsersQuery>) -> ApiResponse {
+    match list_users(state.users.as_ref(), query).await {
+        Ok((users, pagination)) => ApiResponse::paginated("Successfully retrieved users.", json!(users), pagination),
+        Err(e) => ApiResponse::from_error("Failed to retrieve users.", e)
+    }
+}
+
+async fn delete_me_route(State(state): State<AppState>, auth: AuthenticatedUser) -> ApiResponse {
+    let deleted = delete_user(
+        state.users.as_ref(),
+        state.sessions.as_ref(),
+        state.kyc_submissions.as_ref(),
+        state.licensee_records.as_ref(),
+        state.tombstones.as_ref(),
+        auth.wallet_address
+    ).await;
+
+    match deleted {
+        Ok(_) => ApiResponse::success("Successfully deleted user.", None),
+        Err(e) => ApiResponse::from_error("Failed to delete user.", e)
+    }
+}
+
+async fn export_me_route(State(state): State<AppState>, auth: AuthenticatedUser) -> ApiResponse {
+    let export = export_user_data(
+        state.users.as_ref(),
+        state.sessions.as_ref(),
+        state.kyc_submissions.as_ref(),
+        state.licensee_records.as_ref(),
+        auth.wallet_address
+    ).await;
+
+    match export {
+        Ok(export) => ApiResponse::success("Successfully exported user data.", Some(export)),
+        Err(e) => ApiResponse::from_error("Failed to export user data.", e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::{extract::State, http::StatusCode};
+    use ethers::{core::rand::thread_rng, signers::LocalWallet};
+
+    use crate::{
+        models::{UpdateUser, ListUsersQuery, KycSubmission, KycDocument, Licensee, LicenseeRecord},
+        configs::AppState,
+        repositories::InMemoryTombstoneRepository,
+        utils::{RequireOwner, CustomError, Json, Query, testing::{init_test_env, issued_login, sign_up}}
+    };
+
+    use super::{hello_world, create_user_route, update_me_route, list_users_route, delete_me_route, export_me_route};
+
+    fn empty_update() -> UpdateUser {
+        UpdateUser { name: None, dob: None, email: None, phone: None, address: None, company: None }
+    }
+
+    fn list_query(page: usize) -> ListUsersQuery {
+        ListUsersQuery {
+            page: Some(page),
+            page_size: None,
+            sort_by: None,
+            sort_order: None,
+            kyc_verified: None,
+            company: None,
+            created_from: None,
+            created_to: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn hello_world_returns_an_envelope() {
+        let response = hello_world().await;
+
+        assert_eq!(response.status, StatusCode::OK);
+        assert_eq!(response.message, "Hello, World!");
+    }
+
+    #[tokio::test]
+    async fn create_user_route_creates_the_user() {
+        init_test_env();
+
+        let state = AppState::in_memory();
+        let wallet = LocalWallet::new(&mut thread_rng());
+        let login = issued_login(&state, &wallet).await;
+        let wallet_address = login.wallet_address.clone();
+
+        let response = create_user_route(State(state.clone()), Json(login)).await;
+
+        assert_eq!(response.status, StatusCode::OK);
+        assert!(response.data.unwrap()["token"].is_string());
+        assert!(state.users.user_exists(&wallet_address).await.unwrap());
+        assert_eq!(state.sessions.get_sessions(&wallet_address).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn create_user_route_rejects_an_existing_user() {
+        let state = AppState::in_memory();
+        let (wallet, _) = sign_up(&state).await;
+        let login = issued_login(&state, &wallet).await;
+
+        assert_eq!(create_user_route(State(state), Json(login)).await.status, StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    async fn update_me_route_rejects_clearing_a_text_field() {
+        let state = AppState::in_memory();
+        let (_, auth) = sign_up(&state).await;
+
+        let update = UpdateUser { company: Some("  ".to_string()), ..empty_update() };
+
+        assert_eq!(update_me_route(State(state), auth, Json(update)).await.status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn update_me_route_updates_the_given_fields() {
+        let state = AppState::in_memory();
+        let (_, auth) = sign_up(&state).await;
+
+        let update = UpdateUser { email: Some("Licensee@Example.com".to_string()), ..empty_update() };
+        let response = update_me_route(State(state.clone()), auth.clone(), Json(update)).await;
+        assert_eq!(response.status, StatusCode::OK);
+
+        let user = state.users.get_user(&auth.wallet_address).await.unwrap();
+        assert_eq!(user.email.as_deref(), Some("licensee@example.com"));
+        assert_eq!(user.company, None);
+    }
+
+    #[tokio::test]
+    async fn list_users_route_paginates_users() {
+        let state = AppState::in_memory();
+        let (_, owner) = sign_up(&state).await;
+        sign_up(&state).await;
+
+        let query = ListUsersQuery { page_size: Some(1), ..list_query(2) };
+        let response = list_users_route(State(state), RequireOwner(owner), Query(query)).await;
+
+        assert_eq!(response.status, StatusCode::OK);
+        assert_eq!(response.data.unwrap().as_array().unwrap().len(), 1);
+        assert_eq!(response.pagination.unwrap().total_items, 2);
+    }
+
+    #[tokio::test]
+    async fn list_users_route_rejects_a_page_out_of_range() {
+        let state = AppState::in_memory();
+        let (_, owner) = sign_up(&state).await;
+
+        let response = list_users_route(State(state), RequireOwner(owner), Query(list_query(usize::MAX))).await;
+
+        assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn delete_me_route_deletes_everything_but_a_tombstone() {
+        let tombstones = Arc::new(InMemoryTombstoneRepository::new());
+        let state = AppState { tombstones: tombstones.clone(), ..AppState::in_memory() };
+        let (_, auth) = sign_up(&state).await;
+        let wallet_address = auth.wallet_address.clone();
+
+        let document = KycDocument {
+            document_type: "passport".to_string(),
+            file_name: "passport.pdf".to_string(),
+            file_hash: "abc123".to_string(),
+            url: None,
+        };
+        state.kyc_submissions.store_submission(&KycSubmission::new(wallet_address.clone(), vec![document])).await.unwrap();
+
+        let record = LicenseeRecord::new(Licensee {
+            wallet_address: wallet_address.clone(),
+            name: "Jane Doe".to_string(),
+            dob: 0,
+            address: "1 Main Street".to_string(),
+            email_address: "jane@example.com".to_string(),
+            phone_number: "+6512345678".to_string(),
+            company: None,
+            nationality: "SG".to_string(),
+            country_of_application: "SG".to_string(),
+            usable: false,
+        });
+        let record_id = state.licensee_records.store_record(&record).await.unwrap();
+
+        assert_eq!(delete_me_route(State(state.clone()), auth).await.status, StatusCode::OK);
+
+        assert!(!state.users.user_exists(&wallet_address).await.unwrap());
+        assert!(state.sessions.get_sessions(&wallet_address).await.unwrap().is_empty());
+        assert!(state.kyc_submissions.get_submissions(&wallet_address).await.unwrap().is_empty());
+        assert!(matches!(state.licensee_records.get_record(record_id).await, Err(CustomError::NotFound(_))));
+        assert!(tombstones.has_tombstone(&wallet_address));
+    }
+
+    #[tokio::test]
+    async fn delete_me_route_rejects_an_unknown_user() {
+        let state = AppState::in_memory();
+        let (_, auth) = sign_up(&state).await;
+        state.users.delete_user(&auth.wallet_address).await.unwrap();
+
+        assert_eq!(delete_me_route(State(state), auth).await.status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn export_me_route_leaves_out_session_token_hashes() {
+        let state = AppState::in_memory();
+        let (_, auth) = sign_up(&state).await;
+
+        let response = export_me_route(State(state), auth.clone()).await;
+        assert_eq!(response.status, StatusCode::OK);
+
+        let export = response.data.unwrap();
+        let session = &export["sessions"][0];
+
+        assert_eq!(export["walletAddress"], auth.wallet_address);
+        assert_eq!(export["profile"]["wallet_address"], auth.wallet_address);
+        assert!(session.get("token_hash").is_none());
+        assert!(session.get("signature").is_some());
+    }
+}
@@ -1,14 +1,16 @@
-use std::str::FromStr;
-
-use api::{get_license_base_terms, calculate_license_fee, pack_data, create_user, check_user_exists};
-use axum::http::header::{CONTENT_TYPE, ACCESS_CONTROL_ALLOW_ORIGIN};
+use axum::http::header::{CONTENT_TYPE, ACCESS_CONTROL_ALLOW_ORIGIN, AUTHORIZATION};
 use axum::http::{Method, HeaderValue};
-use chrono::{DateTime, Utc, TimeZone};
-use configs::{load_env, get_db, connect_mongo};
-use routes::user_routes;
+use models::ApiResponse;
+use configs::{
+    load_env, bootstrap_db, connect_mongo, AppState, DB_NAME, AuthMode, MIGRATE_ON_STARTUP, SIWE_ALLOWED_DOMAINS, AUTH_MODE, ACCESS_TOKEN_SECRET, KYC_EXPIRY_DAYS,
+    LICENSEE_ENCRYPTION_KEYS, LICENSEE_STORAGE_MODE, LICENSEE_MIN_AGE, LICENSEE_BATCH_SIZE, LICENSEE_RECEIPT_TIMEOUT
+};
+use migrations::run_migrations;
+use routes::{user_routes, auth_routes, kyc_routes, licensee_routes};
 use std::env;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use axum::{routing::get, Router};
+use log::info;
 use tower_http::cors::CorsLayer;
 
 mod api;
@@ -16,10 +18,12 @@ mod configs;
 mod models;
 mod utils;
 mod routes;
+mod repositories;
+mod migrations;
 
 /// Checks to see if Axum is running.
-async fn run_axum() -> &'static str {
-    "Axum is running!"
+async fn run_axum() -> ApiResponse {
+    ApiResponse::success("Axum is running!", None)
 }
 
 #[tokio::main]
@@ -27,7 +31,52 @@ async fn main() {
     tracing_subscriber::fmt::init();
 
     load_env();
-    connect_mongo().await;
+
+    // configs that are read on the request path are loaded here, so that a misconfiguration fails at boot rather than mid-request.
+    lazy_static::initialize(&SIWE_ALLOWED_DOMAINS);
+    lazy_static::initialize(&AUTH_MODE);
+
+    if *AUTH_MODE == AuthMode::Token {
+        lazy_static::initialize(&ACCESS_TOKEN_SECRET);
+    }
+
+    lazy_static::initialize(&KYC_EXPIRY_DAYS);
+    lazy_static::initialize(&LICENSEE_ENCRYPTION_KEYS);
+    lazy_static::initialize(&LICENSEE_STORAGE_MODE);
+    lazy_static::initialize(&LICENSEE_MIN_AGE);
+    lazy_static::initialize(&LICENSEE_BATCH_SIZE);
+    lazy_static::initialize(&LICENSEE_RECEIPT_TIMEOUT);
+
+    // a single MongoDB client is created here and shared by all handlers via `AppState`.
+    let db = connect_mongo().await.database(DB_NAME);
+    let state = AppState::new(&db);
+
+    // `--migrate` applies pending migrations and exits; adding `--dry-run` only reports what would change.
+    let args: Vec<String> = env::args().collect();
+    let migrate_only = args.iter().any(|arg| arg == "--migrate");
+    let dry_run = args.iter().any(|arg| arg == "--dry-run");
+
+    if migrate_only || dry_run {
+        let reports = run_migrations(&db, dry_run).await.expect("Failed to run migrations");
+
+        for report in reports {
+            match (report.already_applied, dry_run) {
+                (true, _) => info!("{}: already applied", report.id),
+                (false, true) => info!("{}: would change {} document(s)", report.id, report.changed),
+                (false, false) => info!("{}: applied, changed {} document(s)", report.id, report.changed),
+            }
+        }
+
+        return;
+    }
+
+    // migrations run before the bootstrap, since indexes (e.g. the unique wallet address index) may depend on migrated documents.
+    if *MIGRATE_ON_STARTUP {
+        run_migrations(&db, false).await.expect("Failed to run migrations");
+    }
+
+    // ensures indexes (and optionally schema validators) exist before any requests are handled.
+    bootstrap_db(&db).await.expect("Failed to bootstrap the database");
 
     let port = env::var("PORT").expect("PORT not set in .env");
     let port = port.parse::<u16>().expect("Invalid port given");
@@ -37,14 +86,18 @@ async fn main() {
 
     // temporarily allowing localhost:3000 for testing
     let cors_middleware = CorsLayer::new()
-        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
+        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::PATCH, Method::DELETE])
         .allow_origin("http://localhost:3000".parse::<HeaderValue>().unwrap())
-        .allow_headers([CONTENT_TYPE, ACCESS_CONTROL_ALLOW_ORIGIN]);
+        .allow_headers([CONTENT_TYPE, ACCESS_CONTROL_ALLOW_ORIGIN, AUTHORIZATION]);
 
     let app = Router::new()
         .route("/", get(run_axum))
         .nest("/user", user_routes())
-        .layer(cors_middleware);
+        .nest("/auth", auth_routes())
+        .nest("/kyc", kyc_routes())
+        .nest("/licensee", licensee_routes())
+        .layer(cors_middleware)
+        .with_state(state);
 
     let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
     axum::serve(listener, app).await.unwrap();
@@ -0,0 +1,179 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::{configs::{ACCESS_TOKEN_SECRET, ACCESS_TOKEN_TTL}, models::Session, utils::CustomError};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// `AccessTokenClaims` represents the payload of a signed access token.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessTokenClaims {
+    /// the (lowercased) wallet address the token was issued to
+    pub wallet_address: String,
+    /// the hex-encoded object ID of the session the token was issued from
+    pub session_id: String,
+    /// when the token expires (UNIX format)
+    pub exp: i64,
+}
+
+/// Issues a short-lived access token for the given session, signed with HMAC-SHA256.
+/// 
+/// The token has the form `<base64url(claims)>.<base64url(signature)>`.
+pub fn issue_access_token(session: &Session) -> Result<String, CustomError> {
+    let claims = AccessTokenClaims {
+        wallet_address: session.wallet_address.to_lowercase(),
+        session_id: session._id.to_hex(),
+        exp: (Utc::now().timestamp() + ACCESS_TOKEN_TTL).min(session.expiration_date),
+    };
+
+    let claims = serde_json::to_vec(&claims)
+        .map_err(|e| CustomError::Auth(format!("Failed to serialize access token claims: {}", e)))?;
+    let claims = URL_SAFE_NO_PAD.encode(claims);
+
+    let mut mac = HmacSha256::new_from_slice(&ACCESS_TOKEN_SECRET)
+        .map_err(|e| CustomError::Auth(format!("Invalid access token secret: {}", e)))?;
+    mac.update(claims.as_bytes());
+    let signature = URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes());
+
+    Ok(format!("{}.{}", claims, signature))
+}
+
+/// Verifies an access token's signature and expiration date, returning its claims if it is valid.
+pub fn verify_access_token(token: &str) -> Result<AccessTokenClaims, CustomError> {
+    let (claims, signature) = token.split_once('.')
+        .ok_or(CustomError::Auth("Malformed access token.".to_string()))?;
+
+    let signature = URL_SAFE_NO_PAD.decode(signature)
+        .map_err(|_| CustomError::Auth("Malformed access token.".to_string()))?;
+
+    let mut mac = HmacSha256::new_from_slice(&ACCESS_TOKEN_SECRET)
+        .map_err(|e| CustomError::Auth(format!("Invalid access token secret: {}", e)))?;
+    mac.update(claims.as_bytes());
+    // `verify_slice` compares in constant time.
+    mac.verify_slice(&signature)
+        .map_err(|_| CustomError::Auth("Invalid access token signature.".to_string()))?;
+
+    let claims = URL_SAFE_NO_PAD.decode(claims)
+        .map_err(|_| CustomError::Auth("Malformed access token.".to_string()))?;
+    let claims: AccessTokenClaims = serde_json::from_slice(&claims)
+        .map_err(|_| CustomError::Auth("Malformed access token.".to_string()))?;
+
+    if claims.exp <= Utc::now().timestamp() {
+        return Err(CustomError::Auth("Access token has expired.".to_string()));
+    }
+
+    Ok(claims)
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+    use chrono::Utc;
+    use hmac::Mac;
+    use mongodb::bson::oid::ObjectId;
+
+    use crate::{models::Session, utils::testing::init_test_env};
+
+    use super::{issue_access_token, verify_access_token, AccessTokenClaims, HmacSha256};
+
+    fn session(expiration_date: i64) -> Session {
+        Session {
+            _id: ObjectId::new(),
+            token_hash: Session::hash_token("token"),
+            expiration_date,
+            expires_at: None,
+            wallet_address: "0x8ba1f109551bD432803012645Ac136ddd64DBA72".to_string(),
+            chain_id: 97,
+            domain: "localhost:3000".to_string(),
+            nonce: "abcdefghijklmnopq".to_string(),
+            signature: "0x".to_string(),
+            payload: None,
+            profile_id: "test-profile".to_string(),
+            uri: "http://localhost:3000".to_string(),
+            version: 1,
+        }
+    }
+
+    /// Signs `claims` the way `issue_access_token` does, but with the given secret.
+    fn sign_claims(claims: &AccessTokenClaims, secret: &[u8]) -> String {
+        let claims = URL_SAFE_NO_PAD.encode(serde_json::to_vec(claims).unwrap());
+
+        let mut mac = HmacSha256::new_from_slice(secret).unwrap();
+        mac.update(claims.as_bytes());
+
+        format!("{}.{}", claims, URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes()))
+    }
+
+    #[test]
+    fn issued_access_tokens_verify() {
+        init_test_env();
+
+        let session = session(Utc::now().timestamp() + 3600);
+        let claims = verify_access_token(&issue_access_token(&session).unwrap()).unwrap();
+
+        assert_eq!(claims.wallet_address, "0x8ba1f109551bd432803012645ac136ddd64dba72");
+        assert_eq!(claims.session_id, session._id.to_hex());
+        assert!(claims.exp > Utc::now().timestamp());
+    }
+
+    #[test]
+    fn access_tokens_expire_with_their_session() {
+        init_test_env();
+
+        let expiration_date = Utc::now().timestamp() + 60;
+        let token = issue_access_token(&session(expiration_date)).unwrap();
+
+        assert_eq!(verify_access_token(&token).unwrap().exp, expiration_date);
+    }
+
+    #[test]
+    fn expired_access_tokens_are_rejected() {
+        init_test_env();
+
+        let token = issue_access_token(&session(Utc::now().timestamp() - 1)).unwrap();
+
+        assert!(verify_access_token(&token).is_err());
+    }
+
+    #[test]
+    fn tampered_access_tokens_are_rejected() {
+        init_test_env();
+
+        let token = issue_access_token(&session(Utc::now().timestamp() + 3600)).unwrap();
+        let (_, signature) = token.split_once('.').unwrap();
+
+        let claims = AccessTokenClaims {
+            wallet_address: "0x0000000000000000000000000000000000000001".to_string(),
+            session_id: ObjectId::new().to_hex(),
+            exp: Utc::now().timestamp() + 3600,
+        };
+        let tampered = format!("{}.{}", URL_SAFE_NO_PAD.encode(serde_json::to_vec(&claims).unwrap()), signature);
+
+        assert!(verify_access_token(&tampered).is_err());
+    }
+
+    #[test]
+    fn access_tokens_signed_with_another_secret_are_rejected() {
+        init_test_env();
+
+        let claims = AccessTokenClaims {
+            wallet_address: "0x8ba1f109551bd432803012645ac136ddd64dba72".to_string(),
+            session_id: ObjectId::new().to_hex(),
+            exp: Utc::now().timestamp() + 3600,
+        };
+
+        assert!(verify_access_token(&sign_claims(&claims, b"another-secret-that-is-32-bytes-long")).is_err());
+    }
+
+    #[test]
+    fn malformed_access_tokens_are_rejected() {
+        init_test_env();
+
+        for token in ["", "no-signature", "not base64.not base64", "."] {
+            assert!(verify_access_token(token).is_err(), "{}", token);
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use std::str::FromStr;
+
+use axum::{async_trait, extract::{FromRequestParts, FromRef}, http::{request::Parts, header::AUTHORIZATION}};
+use chrono::Utc;
+use mongodb::bson::oid::ObjectId;
+
+use crate::{models::Session, utils::{CustomError, verify_access_token, get_roles}, configs::{AUTH_MODE, AuthMode, AppState}};
+
+/// `AuthenticatedUser` is an axum extractor that authenticates a request via its session's bearer token.
+/// 
+/// The token is read from the `Authorization: Bearer <token>` header. Depending on `AUTH_MODE`, it is either a session token
+/// whose `Session` is loaded from the database, or a signed access token that is verified without a database lookup.
+/// Requests without a valid, unexpired token are rejected with `401 Unauthorized`.
+#[derive(Debug, Clone)]
+pub struct AuthenticatedUser {
+    /// the (lowercased) wallet address tied to the session
+    pub wallet_address: String,
+    /// the object ID of the session used to authenticate the request
+    pub session_id: ObjectId,
+}
+
+impl AuthenticatedUser {
+    /// Checks whether the authenticated wallet is an owner (or the main owner) of the License contract.
+    /// 
+    /// The result is cached for `ROLE_CACHE_TTL`.
+    pub async fn is_owner(&self) -> Result<bool, CustomError> {
+        let roles = get_roles(&self.wallet_address).await?;
+
+        Ok(roles.is_owner || roles.is_main_owner)
+    }
+
+    /// Checks whether the authenticated wallet is allowed to access data belonging to `wallet_address`.
+    /// 
+    /// Users may only access their own data, unless they are an owner of the License contract.
+    pub async fn can_access(&self, wallet_address: &str) -> Result<bool, CustomError> {
+        if self.wallet_address.eq_ignore_ascii_case(wallet_address) {
+            return Ok(true);
+        }
+
+        self.is_owner().await
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for AuthenticatedUser
+where
+    AppState: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = CustomError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let token = parts.headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or_else(|| CustomError::Auth("Missing bearer token.".to_string()))?
+            .trim();
+
+        // in token mode, the signed access token is trusted as-is until it expires.
+        if *AUTH_MODE == AuthMode::Token {
+            let claims = verify_access_token(token)?;
+            let session_id = ObjectId::from_str(&claims.session_id)
+                .map_err(|_| CustomError::Auth("Malformed access token.".to_string()))?;
+
+            return Ok(AuthenticatedUser {
+                wallet_address: claims.wallet_address,
+                session_id,
+            });
+        }
+
+        let state = AppState::from_ref(state);
+        let session = state.sessions.get_session_by_token_hash(&Session::hash_token(token)).await?
+            .ok_or_else(|| CustomError::Auth("Invalid session token.".to_string()))?;
+
+        if session.expiration_date <= Utc::now().timestamp() {
+            return Err(CustomError::Auth("Session has expired.".to_string()));
+        }
+
+        Ok(AuthenticatedUser {
+            wallet_address: session.wallet_address.to_lowercase(),
+            session_id: session._id,
+        })
+    }
+}
@@ -18,6 +18,9 @@ abigen!(
     event_derives(serde::Deserialize, serde::Serialize)
 );
 
+/// a state-changing call to the license contract, sent through the signer client
+pub type LicenseCall = ContractCall<SignerMiddleware<Provider<Http>, LocalWallet>, ()>;
+
 // initializes a provider, wallet instance, client and the license contract once
 lazy_static!{
     // creates a provider instance from the RPC URL.
@@ -0,0 +1,111 @@
+use aes_gcm::{aead::{Aead, Payload}, Aes256Gcm, KeyInit};
+use ethers::core::rand::{thread_rng, RngCore};
+
+use crate::utils::CustomError;
+
+/// the length of the random nonce prefixed to sealed data
+const NONCE_LEN: usize = 12;
+/// the length of the authentication tag suffixed to sealed data
+const TAG_LEN: usize = 16;
+/// the amount of bytes that sealing adds to the plaintext
+pub const SEAL_OVERHEAD: usize = NONCE_LEN + TAG_LEN;
+
+/// Encrypts and authenticates `plaintext` (and `aad`, which is authenticated but not encrypted) with the given key using AES-256-GCM.
+///
+/// A random 96-bit nonce is generated for every call. Returns `nonce || ciphertext || tag`.
+/// NOTE: random nonces are safe for up to 2^32 messages per key; every licensee gets their own data key, so this is never approached.
+pub fn seal(key: &[u8; 32], plaintext: &[u8], aad: &[u8]) -> Vec<u8> {
+    let mut nonce = [0u8; NONCE_LEN];
+    thread_rng().fill_bytes(&mut nonce);
+
+    // encryption only fails if the plaintext exceeds AES-GCM's limit of ~64 GiB.
+    let ciphertext = Aes256Gcm::new(key.into())
+        .encrypt(&nonce.into(), Payload { msg: plaintext, aad })
+        .expect("plaintext is too long for AES-256-GCM");
+
+    [nonce.as_slice(), &ciphertext].concat()
+}
+
+/// Verifies and decrypts data sealed by `seal`, returning an error if it was tampered with or sealed with another key or `aad`.
+pub fn open(key: &[u8; 32], sealed: &[u8], aad: &[u8]) -> Result<Vec<u8>, CustomError> {
+    if sealed.len() < SEAL_OVERHEAD {
+        return Err(CustomError::Validation("Encrypted data is truncated.".to_string()));
+    }
+
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    let nonce: [u8; NONCE_LEN] = nonce.try_into().expect("split at the nonce length");
+
+    Aes256Gcm::new(key.into())
+        .decrypt(&nonce.into(), Payload { msg: ciphertext, aad })
+        .map_err(|_| CustomError::Validation("Encrypted data failed authentication.".to_string()))
+}
+
+/// Generates a random 256-bit key.
+pub fn generate_key() -> [u8; 32] {
+    let mut key = [0u8; 32];
+    thread_rng().fill_bytes(&mut key);
+
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::CustomError;
+
+    use super::{seal, open, generate_key, SEAL_OVERHEAD};
+
+    #[test]
+    fn sealed_data_opens_with_the_same_key_and_aad() {
+        let key = generate_key();
+        let sealed = seal(&key, b"licensee data", b"0xabc");
+
+        assert_eq!(sealed.len(), b"licensee data".len() + SEAL_OVERHEAD);
+        assert_eq!(open(&key, &sealed, b"0xabc").unwrap(), b"licensee data");
+    }
+
+    #[test]
+    fn empty_plaintexts_round_trip() {
+        let key = generate_key();
+
+        assert_eq!(open(&key, &seal(&key, b"", b""), b"").unwrap(), b"");
+    }
+
+    #[test]
+    fn sealing_uses_a_fresh_nonce_every_time() {
+        let key = generate_key();
+
+        assert_ne!(seal(&key, b"licensee data", b""), seal(&key, b"licensee data", b""));
+    }
+
+    #[test]
+    fn tampered_data_fails_to_open() {
+        let key = generate_key();
+        let sealed = seal(&key, b"licensee data", b"0xabc");
+
+        // flips a bit in the nonce, the ciphertext and the tag in turn.
+        for index in [0, SEAL_OVERHEAD / 2 + 1, sealed.len() - 1] {
+            let mut tampered = sealed.clone();
+            tampered[index] ^= 1;
+
+            assert!(matches!(open(&key, &tampered, b"0xabc"), Err(CustomError::Validation(_))), "byte {}", index);
+        }
+    }
+
+    #[test]
+    fn data_fails_to_open_with_another_key_or_aad() {
+        let key = generate_key();
+        let sealed = seal(&key, b"licensee data", b"0xabc");
+
+        assert!(open(&generate_key(), &sealed, b"0xabc").is_err());
+        assert!(open(&key, &sealed, b"0xdef").is_err());
+    }
+
+    #[test]
+    fn truncated_data_fails_to_open() {
+        let key = generate_key();
+        let sealed = seal(&key, b"licensee data", b"");
+
+        assert!(open(&key, &sealed[..SEAL_OVERHEAD - 1], b"").is_err());
+        assert!(open(&key, &sealed[..sealed.len() - 1], b"").is_err());
+    }
+}
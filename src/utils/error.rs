@@ -1,24 +1,105 @@
 use std::{fmt::{Formatter, Display}, error::Error};
 
+use axum::{http::StatusCode, response::{IntoResponse, Response}};
+use ethers::{contract::ContractError, providers::Middleware};
+use mongodb::error::{ErrorKind, WriteFailure, BulkWriteFailure};
+
+use crate::{models::ApiResponse, utils::RevertError};
+
+/// the MongoDB error code returned when a write violates a unique index
+const DUPLICATE_KEY_CODE: i32 = 11000;
+
 /// a list of custom error variants
 #[derive(Debug)]
 pub enum CustomError {
     /// database-related errors (from MongoDB)
-    DatabaseError(String),
+    Database(String),
+    /// authentication-related errors (e.g. invalid SIWE signatures or missing bearer tokens)
+    Auth(String),
+    /// authorization-related errors (e.g. a non-owner accessing an owner-only resource)
+    Forbidden(String),
+    /// validation-related errors (e.g. malformed request fields)
+    Validation(String),
+    /// errors caused by a requested resource not existing (e.g. an unknown user)
+    NotFound(String),
+    /// conflict-related errors (e.g. a unique index being violated)
+    Conflict(String),
+    /// errors caused by stored data no longer matching what it is anchored to on-chain (e.g. a tampered licensee record)
+    Tampered(String),
+    /// errors caused by a contract call reverting, decoded into the custom Solidity error and its arguments
+    ContractRevert(RevertError),
+    /// errors caused by the RPC provider failing (e.g. connection issues or malformed responses)
+    Rpc(String),
+}
+
+impl CustomError {
+    /// Returns the HTTP status code that the error maps to.
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            CustomError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            CustomError::Auth(_) => StatusCode::UNAUTHORIZED,
+            CustomError::Forbidden(_) => StatusCode::FORBIDDEN,
+            CustomError::Validation(_) => StatusCode::BAD_REQUEST,
+            CustomError::NotFound(_) => StatusCode::NOT_FOUND,
+            CustomError::Conflict(_) => StatusCode::CONFLICT,
+            CustomError::Tampered(_) => StatusCode::CONFLICT,
+            CustomError::ContractRevert(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            CustomError::Rpc(_) => StatusCode::BAD_GATEWAY,
+        }
+    }
 }
 
 impl Display for CustomError {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
         match self {
-            CustomError::DatabaseError(err) => write!(f, "Database error: {}", err),
+            CustomError::Database(err) => write!(f, "Database error: {}", err),
+            CustomError::Auth(err) => write!(f, "Authentication error: {}", err),
+            CustomError::Forbidden(err) => write!(f, "Forbidden: {}", err),
+            CustomError::Validation(err) => write!(f, "Validation error: {}", err),
+            CustomError::NotFound(err) => write!(f, "Not found: {}", err),
+            CustomError::Conflict(err) => write!(f, "Conflict error: {}", err),
+            CustomError::Tampered(err) => write!(f, "Integrity error: {}", err),
+            CustomError::ContractRevert(err) => write!(f, "Contract reverted: {}", err),
+            CustomError::Rpc(err) => write!(f, "RPC error: {}", err),
         }
     }
 }
 
 impl Error for CustomError {}
 
+impl IntoResponse for CustomError {
+    /// Converts the error into an `ApiResponse` envelope with the error's status code.
+    fn into_response(self) -> Response {
+        ApiResponse::from_error("Failed to process request.", self).into_response()
+    }
+}
+
 impl From<mongodb::error::Error> for CustomError {
     fn from(err: mongodb::error::Error) -> Self {
-        CustomError::DatabaseError(err.to_string())
+        match is_duplicate_key_error(&err) {
+            true => CustomError::Conflict("A document with the same unique key already exists.".to_string()),
+            false => CustomError::Database(err.to_string())
+        }
+    }
+}
+
+impl<M: Middleware> From<ContractError<M>> for CustomError {
+    fn from(err: ContractError<M>) -> Self {
+        match err.as_revert() {
+            Some(data) => CustomError::ContractRevert(RevertError::decode(data)),
+            None => CustomError::Rpc(err.to_string())
+        }
     }
-}
\ No newline at end of file
+}
+
+/// Checks whether `err` was caused by a write violating a unique index.
+fn is_duplicate_key_error(err: &mongodb::error::Error) -> bool {
+    match err.kind.as_ref() {
+        ErrorKind::Write(WriteFailure::WriteError(write_error)) => write_error.code == DUPLICATE_KEY_CODE,
+        ErrorKind::Command(command_error) => command_error.code == DUPLICATE_KEY_CODE,
+        ErrorKind::BulkWrite(BulkWriteFailure { write_errors: Some(write_errors), .. }) => {
+            write_errors.iter().any(|write_error| write_error.code == DUPLICATE_KEY_CODE)
+        },
+        _ => false
+    }
+}
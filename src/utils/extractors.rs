@@ -0,0 +1,75 @@
+use axum::extract::rejection::{JsonRejection, PathRejection, QueryRejection};
+use axum_macros::{FromRequest, FromRequestParts};
+
+use crate::models::ApiResponse;
+
+/// `Json` extracts a JSON request body like `axum::Json`, but rejects malformed bodies with an `ApiResponse` envelope instead of plain text.
+#[derive(Debug, FromRequest)]
+#[from_request(via(axum::Json), rejection(ApiResponse))]
+pub struct Json<T>(pub T);
+
+/// `Query` extracts a request's query string like `axum::extract::Query`, but rejects malformed queries with an `ApiResponse` envelope instead of plain text.
+#[derive(Debug, FromRequestParts)]
+#[from_request(via(axum::extract::Query), rejection(ApiResponse))]
+pub struct Query<T>(pub T);
+
+/// `Path` extracts path parameters like `axum::extract::Path`, but rejects malformed parameters with an `ApiResponse` envelope instead of plain text.
+#[derive(Debug, FromRequestParts)]
+#[from_request(via(axum::extract::Path), rejection(ApiResponse))]
+pub struct Path<T>(pub T);
+
+impl From<JsonRejection> for ApiResponse {
+    fn from(rejection: JsonRejection) -> Self {
+        ApiResponse::error(rejection.status(), "Invalid request body.", rejection.body_text())
+    }
+}
+
+impl From<QueryRejection> for ApiResponse {
+    fn from(rejection: QueryRejection) -> Self {
+        ApiResponse::error(rejection.status(), "Invalid query string.", rejection.body_text())
+    }
+}
+
+impl From<PathRejection> for ApiResponse {
+    fn from(rejection: PathRejection) -> Self {
+        ApiResponse::error(rejection.status(), "Invalid path parameters.", rejection.body_text())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::{to_bytes, Body}, extract::{FromRequest, FromRequestParts}, http::{header::CONTENT_TYPE, Request, StatusCode}, response::IntoResponse};
+    use serde_json::Value;
+
+    use crate::models::{CreateUser, ListUsersQuery};
+
+    use super::{Json, Query};
+
+    #[tokio::test]
+    async fn malformed_json_bodies_are_rejected_with_an_envelope() {
+        let request = Request::builder()
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from("{ not json"))
+            .unwrap();
+
+        let rejection = Json::<CreateUser>::from_request(request, &()).await.unwrap_err();
+        assert_eq!(rejection.status, StatusCode::BAD_REQUEST);
+        assert!(rejection.error.is_some());
+
+        let response = rejection.into_response();
+        assert_eq!(response.headers()[CONTENT_TYPE], "application/json");
+
+        let body: Value = serde_json::from_slice(&to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap();
+        assert_eq!(body["message"], "Invalid request body.");
+    }
+
+    #[tokio::test]
+    async fn malformed_query_strings_are_rejected_with_an_envelope() {
+        let (mut parts, _) = Request::builder().uri("/user?page=abc").body(()).unwrap().into_parts();
+
+        let rejection = Query::<ListUsersQuery>::from_request_parts(&mut parts, &()).await.unwrap_err();
+        assert_eq!(rejection.status, StatusCode::BAD_REQUEST);
+        assert_eq!(rejection.message, "Invalid query string.");
+        assert!(rejection.error.is_some());
+    }
+}
@@ -2,8 +2,26 @@ pub mod contract_base;
 pub mod serialization;
 pub mod error;
 pub mod response_handler;
+pub mod siwe;
+pub mod auth;
+pub mod access_token;
+pub mod roles;
+pub mod validation;
+pub mod revert;
+pub mod encryption;
+pub mod extractors;
+#[cfg(test)]
+pub mod testing;
 
 pub use contract_base::*;
 pub use serialization::*;
 pub use error::*;
-pub use response_handler::*;
\ No newline at end of file
+pub use response_handler::*;
+pub use siwe::*;
+pub use auth::*;
+pub use access_token::*;
+pub use roles::*;
+pub use validation::*;
+pub use revert::*;
+pub use encryption::*;
+pub use extractors::*;
@@ -0,0 +1,63 @@
+use axum::{http::StatusCode, response::{IntoResponse, Response}, Json};
+use serde_json::{json, Value};
+
+use crate::{models::{ApiResponse, Pagination}, utils::CustomError};
+
+/// the current version of the API, included in every response
+pub const API_VERSION: u8 = 1;
+
+impl ApiResponse {
+    /// Creates a `200 OK` response with the given message and (optional) data.
+    pub fn success(message: &str, data: Option<Value>) -> Self {
+        Self {
+            status: StatusCode::OK,
+            error: None,
+            message: message.to_string(),
+            data,
+            pagination: None,
+            version: API_VERSION,
+        }
+    }
+
+    /// Creates a `200 OK` response containing a single page of data along with its pagination data.
+    pub fn paginated(message: &str, data: Value, pagination: Pagination) -> Self {
+        Self {
+            pagination: Some(pagination),
+            ..Self::success(message, Some(data))
+        }
+    }
+
+    /// Creates an error response with the given status code, message and error.
+    pub fn error(status: StatusCode, message: &str, error: String) -> Self {
+        Self {
+            status,
+            error: Some(error),
+            message: message.to_string(),
+            data: None,
+            pagination: None,
+            version: API_VERSION,
+        }
+    }
+
+    /// Creates an error response from a `CustomError`, using the status code that the error maps to.
+    ///
+    /// Contract reverts additionally expose their error code and arguments in `data` so that clients can act on them.
+    pub fn from_error(message: &str, err: CustomError) -> Self {
+        let data = match &err {
+            CustomError::ContractRevert(revert) => Some(json!(revert)),
+            _ => None
+        };
+
+        Self {
+            data,
+            ..Self::error(err.status_code(), message, err.to_string())
+        }
+    }
+}
+
+impl IntoResponse for ApiResponse {
+    /// Serializes the response as JSON (with `Content-Type: application/json`), using its `status` as the HTTP status code.
+    fn into_response(self) -> Response {
+        (self.status, Json(self)).into_response()
+    }
+}
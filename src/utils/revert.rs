@@ -0,0 +1,210 @@
+use std::fmt::{Display, Formatter};
+
+use ethers::{contract::ContractRevert, types::{Address, Bytes, U256}, utils::{hex::encode_prefixed, to_checksum}};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use super::LicenseContractErrors;
+
+/// `RevertError` is a machine-readable version of a revert from the License contract.
+///
+/// `code` is the name of the custom Solidity error (e.g. "RoyaltyAmountMismatch"), and `args` contains its arguments keyed by
+/// their ABI names (e.g. "royaltyDue"). Addresses are checksummed, and amounts are decimal strings so that they don't lose precision.
+#[derive(Debug, Clone, Serialize)]
+pub struct RevertError {
+    /// the name of the custom error, "Revert" for plain revert strings or "UnknownRevert" if the data couldn't be decoded
+    pub code: String,
+    /// the error's arguments, keyed by their ABI names
+    pub args: Map<String, Value>,
+}
+
+impl RevertError {
+    /// Decodes the revert data of a failed License contract call via the abigen-generated `LicenseContractErrors`.
+    pub fn decode(data: &Bytes) -> Self {
+        let decoded = match LicenseContractErrors::decode_with_selector(data) {
+            Some(decoded) => decoded,
+            None => return Self::new("UnknownRevert", vec![("data", data.to_revert_arg())])
+        };
+
+        let (code, args) = match decoded {
+            LicenseContractErrors::AccountStatusNotPending(err) => ("AccountStatusNotPending", vec![("licensee", err.licensee.to_revert_arg())]),
+            LicenseContractErrors::AlreadyAppliedForLicense(err) => ("AlreadyAppliedForLicense", vec![("licensee", err.licensee.to_revert_arg()), ("licenseHash", err.license_hash.to_revert_arg())]),
+            LicenseContractErrors::ApplicationAlreadyPaid(err) => ("ApplicationAlreadyPaid", vec![("licensee", err.licensee.to_revert_arg()), ("applicationHash", err.application_hash.to_revert_arg())]),
+            LicenseContractErrors::ApplicationNotFound(err) => ("ApplicationNotFound", vec![("licensee", err.licensee.to_revert_arg()), ("applicationHash", err.application_hash.to_revert_arg())]),
+            LicenseContractErrors::ApplicationNotPaid(err) => ("ApplicationNotPaid", vec![("licensee", err.licensee.to_revert_arg()), ("applicationHash", err.application_hash.to_revert_arg())]),
+            LicenseContractErrors::ECDSAInvalidSignature(_) => ("ECDSAInvalidSignature", vec![]),
+            LicenseContractErrors::ECDSAInvalidSignatureLength(err) => ("ECDSAInvalidSignatureLength", vec![("length", err.length.to_revert_arg())]),
+            LicenseContractErrors::ECDSAInvalidSignatureS(err) => ("ECDSAInvalidSignatureS", vec![("s", err.s.to_revert_arg())]),
+            LicenseContractErrors::EmptyBaseTerms(_) => ("EmptyBaseTerms", vec![]),
+            LicenseContractErrors::EmptyLicenseHash(_) => ("EmptyLicenseHash", vec![]),
+            LicenseContractErrors::EmptyLicenseeData(_) => ("EmptyLicenseeData", vec![]),
+            LicenseContractErrors::EmptyNewTerms(_) => ("EmptyNewTerms", vec![]),
+            LicenseContractErrors::InvalidExpirationDate(err) => ("InvalidExpirationDate", vec![("expirationDate", err.expiration_date.to_revert_arg())]),
+            LicenseContractErrors::InvalidExtraDataLength(err) => ("InvalidExtraDataLength", vec![("extraData", err.extra_data.to_revert_arg())]),
+            LicenseContractErrors::InvalidLicenseFee(err) => ("InvalidLicenseFee", vec![("licenseFee", err.license_fee.to_revert_arg())]),
+            LicenseContractErrors::InvalidLicenseeAddress(err) => ("InvalidLicenseeAddress", vec![("licensee", err.licensee.to_revert_arg())]),
+            LicenseContractErrors::InvalidReceiverAddress(err) => ("InvalidReceiverAddress", vec![("receiver", err.receiver.to_revert_arg())]),
+            LicenseContractErrors::InvalidSignature(err) => ("InvalidSignature", vec![("recoveredAddress", err.recovered_address.to_revert_arg()), ("licensee", err.licensee.to_revert_arg())]),
+            LicenseContractErrors::LicenseAlreadyExists(err) => ("LicenseAlreadyExists", vec![("licenseHash", err.license_hash.to_revert_arg())]),
+            LicenseContractErrors::LicenseAlreadyUsable(err) => ("LicenseAlreadyUsable", vec![("licensee", err.licensee.to_revert_arg()), ("applicationHash", err.application_hash.to_revert_arg())]),
+            LicenseContractErrors::LicenseDoesntExist(err) => ("LicenseDoesntExist", vec![("licenseHash", err.license_hash.to_revert_arg())]),
+            LicenseContractErrors::LicenseNotUsable(err) => ("LicenseNotUsable", vec![("licensee", err.licensee.to_revert_arg()), ("applicationHash", err.application_hash.to_revert_arg())]),
+            LicenseContractErrors::LicenseeAlreadyExists(err) => ("LicenseeAlreadyExists", vec![("licensee", err.licensee.to_revert_arg())]),
+            LicenseContractErrors::LicenseeDoesntExist(err) => ("LicenseeDoesntExist", vec![("licensee", err.licensee.to_revert_arg())]),
+            LicenseContractErrors::LicenseeNoApplications(err) => ("LicenseeNoApplications", vec![("licensee", err.licensee.to_revert_arg())]),
+            LicenseContractErrors::LicenseeNotApproved(err) => ("LicenseeNotApproved", vec![("licensee", err.licensee.to_revert_arg())]),
+            LicenseContractErrors::MultiOwnableAlreadyOwner(err) => ("MultiOwnableAlreadyOwner", vec![("owner", err.owner.to_revert_arg())]),
+            LicenseContractErrors::MultiOwnableInvalidOwner(err) => ("MultiOwnableInvalidOwner", vec![("owner", err.owner.to_revert_arg())]),
+            LicenseContractErrors::MultiOwnableNotMainOwner(err) => ("MultiOwnableNotMainOwner", vec![("caller", err.caller.to_revert_arg())]),
+            LicenseContractErrors::MultiOwnableUnauthorized(err) => ("MultiOwnableUnauthorized", vec![("caller", err.caller.to_revert_arg())]),
+            LicenseContractErrors::NewReportNotYetAllowed(err) => ("NewReportNotYetAllowed", vec![("licensee", err.licensee.to_revert_arg()), ("applicationHash", err.application_hash.to_revert_arg())]),
+            LicenseContractErrors::NoReportsFound(err) => ("NoReportsFound", vec![("licensee", err.licensee.to_revert_arg()), ("applicationHash", err.application_hash.to_revert_arg())]),
+            LicenseContractErrors::NotOwnerOrLicenseOwner(err) => ("NotOwnerOrLicenseOwner", vec![("caller", err.caller.to_revert_arg()), ("applicationHash", err.application_hash.to_revert_arg())]),
+            LicenseContractErrors::NotOwnerOrLicensee(err) => ("NotOwnerOrLicensee", vec![("caller", err.caller.to_revert_arg())]),
+            LicenseContractErrors::NotOwnerOrOwnLicensee(err) => ("NotOwnerOrOwnLicensee", vec![("caller", err.caller.to_revert_arg()), ("queriedLicensee", err.queried_licensee.to_revert_arg())]),
+            LicenseContractErrors::ReportAlreadyApproved(err) => ("ReportAlreadyApproved", vec![("licensee", err.licensee.to_revert_arg()), ("applicationHash", err.application_hash.to_revert_arg()), ("reportIndex", err.report_index.to_revert_arg())]),
+            LicenseContractErrors::ReportDoesntExist(err) => ("ReportDoesntExist", vec![("licensee", err.licensee.to_revert_arg()), ("applicationHash", err.application_hash.to_revert_arg())]),
+            LicenseContractErrors::ReportNotYetApproved(err) => ("ReportNotYetApproved", vec![("licensee", err.licensee.to_revert_arg()), ("applicationHash", err.application_hash.to_revert_arg()), ("reportIndex", err.report_index.to_revert_arg())]),
+            LicenseContractErrors::RoyaltyAlreadyPaid(err) => ("RoyaltyAlreadyPaid", vec![("licensee", err.licensee.to_revert_arg()), ("applicationHash", err.application_hash.to_revert_arg()), ("reportIndex", err.report_index.to_revert_arg())]),
+            LicenseContractErrors::RoyaltyAmountMismatch(err) => ("RoyaltyAmountMismatch", vec![("licensee", err.licensee.to_revert_arg()), ("applicationHash", err.application_hash.to_revert_arg()), ("reportIndex", err.report_index.to_revert_arg()), ("royaltyDue", err.royalty_due.to_revert_arg()), ("royaltyToPay", err.royalty_to_pay.to_revert_arg())]),
+            LicenseContractErrors::RoyaltyNoDeadline(err) => ("RoyaltyNoDeadline", vec![("licensee", err.licensee.to_revert_arg()), ("applicationHash", err.application_hash.to_revert_arg()), ("reportIndex", err.report_index.to_revert_arg())]),
+            LicenseContractErrors::RoyaltyPaymentNotYetNeeded(err) => ("RoyaltyPaymentNotYetNeeded", vec![("licensee", err.licensee.to_revert_arg()), ("applicationHash", err.application_hash.to_revert_arg()), ("reportIndex", err.report_index.to_revert_arg())]),
+            LicenseContractErrors::SameBaseTerms(err) => ("SameBaseTerms", vec![("url", err.url.to_revert_arg())]),
+            LicenseContractErrors::SameLicenseeData(err) => ("SameLicenseeData", vec![("data", err.data.to_revert_arg())]),
+            LicenseContractErrors::SameReceiverAddress(_) => ("SameReceiverAddress", vec![]),
+            LicenseContractErrors::UntimelyReportNotRequired(err) => ("UntimelyReportNotRequired", vec![("licensee", err.licensee.to_revert_arg()), ("applicationHash", err.application_hash.to_revert_arg()), ("reportIndex", err.report_index.to_revert_arg())]),
+            LicenseContractErrors::RevertString(reason) => ("Revert", vec![("reason", reason.to_revert_arg())]),
+        };
+
+        Self::new(code, args)
+    }
+
+    fn new(code: &str, args: Vec<(&str, Value)>) -> Self {
+        Self {
+            code: code.to_string(),
+            args: args.into_iter().map(|(name, value)| (name.to_string(), value)).collect(),
+        }
+    }
+}
+
+impl Display for RevertError {
+    /// Formats the error as e.g. `RoyaltyAmountMismatch(royaltyDue: 100, royaltyToPay: 90)`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        let args: Vec<String> = self.args.iter()
+            .map(|(name, value)| match value.as_str() {
+                Some(value) => format!("{}: {}", name, value),
+                None => format!("{}: {}", name, value)
+            })
+            .collect();
+
+        write!(f, "{}({})", self.code, args.join(", "))
+    }
+}
+
+/// Converts a decoded revert argument into its JSON representation.
+trait RevertArg {
+    fn to_revert_arg(&self) -> Value;
+}
+
+impl RevertArg for Address {
+    fn to_revert_arg(&self) -> Value {
+        Value::String(to_checksum(self, None))
+    }
+}
+
+impl RevertArg for U256 {
+    fn to_revert_arg(&self) -> Value {
+        Value::String(self.to_string())
+    }
+}
+
+impl RevertArg for [u8; 32] {
+    fn to_revert_arg(&self) -> Value {
+        Value::String(encode_prefixed(self))
+    }
+}
+
+impl RevertArg for Bytes {
+    fn to_revert_arg(&self) -> Value {
+        Value::String(encode_prefixed(self))
+    }
+}
+
+impl RevertArg for String {
+    fn to_revert_arg(&self) -> Value {
+        Value::String(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::{abi::{encode, AbiEncode, Token}, contract::EthError, types::{Address, Bytes, U256}, utils::hex::decode};
+
+    use crate::utils::{RoyaltyAmountMismatch, LicenseeAlreadyExists, EmptyLicenseeData};
+
+    use super::RevertError;
+
+    /// Encodes a custom error the way the EVM returns it, i.e. its selector followed by its ABI-encoded arguments.
+    fn revert_data<E: EthError + AbiEncode>(error: E) -> Bytes {
+        error.encode().into()
+    }
+
+    #[test]
+    fn decodes_custom_errors_with_their_arguments() {
+        let licensee: Address = "0x8ba1f109551bd432803012645ac136ddd64dba72".parse().unwrap();
+        let revert = RevertError::decode(&revert_data(RoyaltyAmountMismatch {
+            licensee,
+            application_hash: [1; 32],
+            report_index: U256::from(2),
+            royalty_due: U256::exp10(30),
+            royalty_to_pay: U256::from(90),
+        }));
+
+        assert_eq!(revert.code, "RoyaltyAmountMismatch");
+        assert_eq!(revert.args["licensee"], "0x8ba1f109551bD432803012645Ac136ddd64DBA72");
+        assert_eq!(revert.args["applicationHash"], format!("0x{}", "01".repeat(32)));
+        assert_eq!(revert.args["reportIndex"], "2");
+        // amounts are decimal strings, so that they don't lose precision.
+        assert_eq!(revert.args["royaltyDue"], format!("1{}", "0".repeat(30)));
+        assert_eq!(revert.args["royaltyToPay"], "90");
+    }
+
+    #[test]
+    fn decodes_custom_errors_without_arguments() {
+        let revert = RevertError::decode(&revert_data(EmptyLicenseeData));
+
+        assert_eq!(revert.code, "EmptyLicenseeData");
+        assert!(revert.args.is_empty());
+        assert_eq!(revert.to_string(), "EmptyLicenseeData()");
+    }
+
+    #[test]
+    fn decodes_revert_strings() {
+        // the selector of `Error(string)`
+        let selector = decode("08c379a0").unwrap();
+        let data: Bytes = [selector, encode(&[Token::String("Ownable: caller is not the owner".to_string())])].concat().into();
+
+        let revert = RevertError::decode(&data);
+
+        assert_eq!(revert.code, "Revert");
+        assert_eq!(revert.args["reason"], "Ownable: caller is not the owner");
+    }
+
+    #[test]
+    fn keeps_the_data_of_unknown_reverts() {
+        let data: Bytes = vec![0xde, 0xad, 0xbe, 0xef, 0x01].into();
+
+        let revert = RevertError::decode(&data);
+
+        assert_eq!(revert.code, "UnknownRevert");
+        assert_eq!(revert.args["data"], "0xdeadbeef01");
+    }
+
+    #[test]
+    fn formats_arguments_in_order() {
+        let licensee: Address = "0x8ba1f109551bd432803012645ac136ddd64dba72".parse().unwrap();
+        let revert = RevertError::decode(&revert_data(LicenseeAlreadyExists { licensee }));
+
+        assert_eq!(revert.to_string(), "LicenseeAlreadyExists(licensee: 0x8ba1f109551bD432803012645Ac136ddd64DBA72)");
+    }
+}
@@ -0,0 +1,127 @@
+use std::{collections::HashMap, str::FromStr, sync::RwLock, time::{Duration, Instant}};
+
+use axum::{async_trait, extract::{FromRequestParts, FromRef}, http::request::Parts};
+use ethers::types::H160;
+use lazy_static::lazy_static;
+
+use crate::{utils::{AuthenticatedUser, CustomError, LICENSE}, configs::AppState};
+
+/// how long a wallet's roles are cached for before `MultiOwnable.sol` is queried again
+pub const ROLE_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// the maximum amount of wallets whose roles are cached at once
+pub const ROLE_CACHE_MAX_ENTRIES: usize = 10_000;
+
+/// `Roles` represents the admin roles a wallet has on the License contract.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Roles {
+    /// whether the wallet is an owner (via `MultiOwnable.sol - isOwner`)
+    pub is_owner: bool,
+    /// whether the wallet is the main owner (via `MultiOwnable.sol - isMainOwner`)
+    pub is_main_owner: bool,
+}
+
+lazy_static! {
+    // caches each wallet's roles together with when they were fetched.
+    static ref ROLE_CACHE: RwLock<HashMap<String, (Roles, Instant)>> = RwLock::new(HashMap::new());
+}
+
+/// Gets the roles of the given wallet address, calling the License contract if they are not cached (or the cache is stale).
+pub async fn get_roles(wallet_address: &str) -> Result<Roles, CustomError> {
+    let wallet_address = wallet_address.to_lowercase();
+
+    if let Some((roles, fetched_at)) = ROLE_CACHE.read().unwrap().get(&wallet_address) {
+        if fetched_at.elapsed() < ROLE_CACHE_TTL {
+            return Ok(*roles);
+        }
+    }
+
+    let address = H160::from_str(&wallet_address)
+        .map_err(|e| CustomError::Validation(format!("Invalid wallet address: {}", e)))?;
+
+    let is_owner_call = LICENSE.is_owner(address);
+    let is_main_owner_call = LICENSE.is_main_owner(address);
+    let (is_owner, is_main_owner) = futures::try_join!(is_owner_call.call(), is_main_owner_call.call())
+        .map_err(CustomError::from)?;
+
+    let roles = Roles { is_owner, is_main_owner };
+    let mut cache = ROLE_CACHE.write().unwrap();
+
+    // any authenticated wallet ends up in the cache, so stale entries are evicted before it can grow past `ROLE_CACHE_MAX_ENTRIES`.
+    if cache.len() >= ROLE_CACHE_MAX_ENTRIES {
+        cache.retain(|_, (_, fetched_at)| fetched_at.elapsed() < ROLE_CACHE_TTL);
+    }
+
+    // if every entry is still fresh, the oldest one makes room instead.
+    if cache.len() >= ROLE_CACHE_MAX_ENTRIES {
+        let oldest = cache.iter()
+            .min_by_key(|(_, (_, fetched_at))| *fetched_at)
+            .map(|(wallet_address, _)| wallet_address.clone());
+
+        if let Some(oldest) = oldest {
+            cache.remove(&oldest);
+        }
+    }
+
+    cache.insert(wallet_address, (roles, Instant::now()));
+
+    Ok(roles)
+}
+
+/// Caches the given roles for a wallet address, so that tests don't need to reach the License contract.
+#[cfg(test)]
+pub fn cache_roles(wallet_address: &str, roles: Roles) {
+    ROLE_CACHE.write().unwrap().insert(wallet_address.to_lowercase(), (roles, Instant::now()));
+}
+
+/// `RequireOwner` is an axum extractor that only lets owners (or the main owner) of the License contract through.
+/// 
+/// Non-owners are rejected with `403 Forbidden`.
+#[derive(Debug, Clone)]
+pub struct RequireOwner(pub AuthenticatedUser);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for RequireOwner
+where
+    AppState: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = CustomError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let auth = AuthenticatedUser::from_request_parts(parts, state).await?;
+        let roles = get_roles(&auth.wallet_address).await?;
+
+        if !roles.is_owner && !roles.is_main_owner {
+            return Err(CustomError::Forbidden("Only owners can access this resource.".to_string()));
+        }
+
+        Ok(RequireOwner(auth))
+    }
+}
+
+/// `RequireMainOwner` is an axum extractor that only lets the main owner of the License contract through.
+/// 
+/// Everyone else (including regular owners) is rejected with `403 Forbidden`.
+#[derive(Debug, Clone)]
+pub struct RequireMainOwner(pub AuthenticatedUser);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for RequireMainOwner
+where
+    AppState: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = CustomError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let auth = AuthenticatedUser::from_request_parts(parts, state).await?;
+        let roles = get_roles(&auth.wallet_address).await?;
+
+        if !roles.is_main_owner {
+            return Err(CustomError::Forbidden("Only the main owner can access this resource.".to_string()));
+        }
+
+        Ok(RequireMainOwner(auth))
+    }
+}
@@ -0,0 +1,360 @@
+use ethers::utils::keccak256;
+use mongodb::bson::oid::ObjectId;
+
+use crate::{models::Licensee, utils::{CustomError, seal, open, generate_key, SEAL_OVERHEAD}, configs::LicenseeKeyring};
+
+/// the version byte prefixed to licensee data encoded by `encode_licensee_data`
+/// 
+/// NOTE: legacy (pipe-separated) data is plain UTF-8 text starting with the wallet address, so it never starts with a control byte.
+pub const LICENSEE_DATA_VERSION: u8 = 1;
+
+/// the version byte prefixed to licensee data encrypted by `encrypt_licensee_data`
+pub const LICENSEE_DATA_VERSION_ENCRYPTED: u8 = 2;
+
+/// the version byte prefixed to commitments encoded by `encode_licensee_commitment`
+pub const LICENSEE_DATA_VERSION_COMMITMENT: u8 = 3;
+
+/// the length of a licensee's data key once it is wrapped by the server key
+const WRAPPED_KEY_LEN: usize = 32 + SEAL_OVERHEAD;
+
+/// Encodes a licensee's account information into the versioned binary format stored in `Licensee.sol`.
+/// 
+/// The format is the version byte followed by each field in order; strings are prefixed with their length as a big-endian `u32`
+/// (so they may contain any character), and `dob` is a big-endian `i64`. A missing company is encoded as an empty string.
+/// NOTE: `usable` is managed by the contract itself and is therefore not encoded.
+pub fn encode_licensee_data(licensee: &Licensee) -> Vec<u8> {
+    fn push_string(bytes: &mut Vec<u8>, value: &str) {
+        bytes.extend_from_slice(&(value.len() as u32).to_be_bytes());
+        bytes.extend_from_slice(value.as_bytes());
+    }
+
+    let mut bytes = vec![LICENSEE_DATA_VERSION];
+
+    push_string(&mut bytes, &licensee.wallet_address);
+    push_string(&mut bytes, &licensee.name);
+    bytes.extend_from_slice(&licensee.dob.to_be_bytes());
+    push_string(&mut bytes, &licensee.address);
+    push_string(&mut bytes, &licensee.email_address);
+    push_string(&mut bytes, &licensee.phone_number);
+    push_string(&mut bytes, licensee.company.as_deref().unwrap_or_default());
+    push_string(&mut bytes, &licensee.nationality);
+    push_string(&mut bytes, &licensee.country_of_application);
+
+    bytes
+}
+
+/// Decodes licensee data encoded by `encode_licensee_data` (excluding the version byte).
+/// 
+/// Returns an error naming the field that is truncated or malformed.
+pub fn decode_licensee_data_v1(bytes: &[u8], usable: bool) -> Result<Licensee, CustomError> {
+    let mut reader = FieldReader { bytes };
+
+    let licensee = Licensee {
+        wallet_address: reader.read_string("wallet_address")?,
+        name: reader.read_string("name")?,
+        dob: reader.read_i64("dob")?,
+        address: reader.read_string("address")?,
+        email_address: reader.read_string("email_address")?,
+        phone_number: reader.read_string("phone_number")?,
+        company: Some(reader.read_string("company")?).filter(|company| !company.is_empty()),
+        nationality: reader.read_string("nationality")?,
+        country_of_application: reader.read_string("country_of_application")?,
+        usable
+    };
+
+    if !reader.bytes.is_empty() {
+        return Err(CustomError::Validation(format!("Licensee data has {} unexpected trailing byte(s).", reader.bytes.len())));
+    }
+
+    Ok(licensee)
+}
+
+/// Envelope-encrypts licensee data encoded by `encode_licensee_data`, so that no PII is written on-chain in plaintext.
+/// 
+/// A random data key is generated for the licensee and used to seal the data (see `seal`); the data key is in turn sealed ("wrapped") by the keyring's current server key.
+/// Both are bound to `licensee_address`, so the ciphertext can't be copied to (and decrypted for) another licensee account.
+/// The format is the version byte, followed by the server key's ID, the wrapped data key and the sealed data.
+pub fn encrypt_licensee_data(encoded: &[u8], licensee_address: &str, keyring: &LicenseeKeyring) -> Vec<u8> {
+    let licensee_address = licensee_address.to_lowercase();
+    let (key_id, server_key) = keyring.current();
+    let data_key = generate_key();
+
+    let wrapped_key = seal(server_key, &data_key, &wrapped_key_aad(key_id, &licensee_address));
+    let sealed_data = seal(&data_key, encoded, licensee_address.as_bytes());
+
+    [&[LICENSEE_DATA_VERSION_ENCRYPTED, key_id], wrapped_key.as_slice(), &sealed_data].concat()
+}
+
+/// Decrypts licensee data encrypted by `encrypt_licensee_data` (excluding the version byte), returning the encoded data.
+/// 
+/// The data key is unwrapped with whichever of the keyring's server keys the data was encrypted with, so data written before a key rotation remains readable.
+pub fn decrypt_licensee_data(bytes: &[u8], licensee_address: &str, keyring: &LicenseeKeyring) -> Result<Vec<u8>, CustomError> {
+    let (&key_id, bytes) = bytes.split_first()
+        .filter(|(_, bytes)| bytes.len() >= WRAPPED_KEY_LEN)
+        .ok_or(CustomError::Validation("Encrypted licensee data is truncated.".to_string()))?;
+
+    let server_key = keyring.get(key_id)
+        .ok_or(CustomError::Validation(format!("Licensee data is encrypted with unknown key {}.", key_id)))?;
+
+    let licensee_address = licensee_address.to_lowercase();
+    let (wrapped_key, sealed_data) = bytes.split_at(WRAPPED_KEY_LEN);
+
+    let data_key: [u8; 32] = open(server_key, wrapped_key, &wrapped_key_aad(key_id, &licensee_address))?
+        .try_into()
+        .map_err(|_| CustomError::Validation("Licensee data key has an invalid length.".to_string()))?;
+
+    open(&data_key, sealed_data, licensee_address.as_bytes())
+}
+
+/// the additional authenticated data used when wrapping a licensee's data key with the server key `key_id`
+fn wrapped_key_aad(key_id: u8, licensee_address: &str) -> Vec<u8> {
+    [b"licensee-data-key:".as_slice(), &[key_id], licensee_address.as_bytes()].concat()
+}
+
+/// Computes the salted commitment of a licensee's account information, i.e. `keccak256(salt || encode_licensee_data(licensee))`.
+/// 
+/// The salt prevents the (low-entropy) account information from being brute-forced from the commitment.
+pub fn licensee_commitment(licensee: &Licensee, salt: &[u8; 32]) -> [u8; 32] {
+    keccak256([salt.as_slice(), &encode_licensee_data(licensee)].concat())
+}
+
+/// Encodes a commitment to an off-chain licensee record, to be stored in `Licensee.sol` instead of the account information itself.
+/// 
+/// The format is the version byte, followed by the record's `ObjectId` (12 bytes) and the commitment (32 bytes).
+pub fn encode_licensee_commitment(record_id: &ObjectId, commitment: &[u8; 32]) -> Vec<u8> {
+    [&[LICENSEE_DATA_VERSION_COMMITMENT], record_id.bytes().as_slice(), commitment].concat()
+}
+
+/// Decodes a commitment encoded by `encode_licensee_commitment` (excluding the version byte).
+/// 
+/// Returns the `ObjectId` of the off-chain record and the commitment.
+pub fn decode_licensee_commitment(bytes: &[u8]) -> Result<(ObjectId, [u8; 32]), CustomError> {
+    let mut reader = FieldReader { bytes };
+
+    let record_id: [u8; 12] = reader.take(12, "record_id")?.try_into().unwrap_or_default();
+    let commitment: [u8; 32] = reader.take(32, "commitment")?.try_into().unwrap_or_default();
+
+    if !reader.bytes.is_empty() {
+        return Err(CustomError::Validation(format!("Licensee commitment has {} unexpected trailing byte(s).", reader.bytes.len())));
+    }
+
+    Ok((ObjectId::from_bytes(record_id), commitment))
+}
+
+/// `FieldReader` reads the fields of versioned licensee data one by one.
+struct FieldReader<'a> {
+    /// the bytes that haven't been read yet
+    bytes: &'a [u8],
+}
+
+impl<'a> FieldReader<'a> {
+    /// Reads the next `len` bytes, returning an error naming `field` if there aren't enough left.
+    fn take(&mut self, len: usize, field: &str) -> Result<&'a [u8], CustomError> {
+        if self.bytes.len() < len {
+            return Err(CustomError::Validation(format!("Licensee data is truncated at the {} field.", field)));
+        }
+
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+
+        Ok(taken)
+    }
+
+    fn read_i64(&mut self, field: &str) -> Result<i64, CustomError> {
+        let bytes = self.take(8, field)?;
+
+        Ok(i64::from_be_bytes(bytes.try_into().unwrap_or_default()))
+    }
+
+    fn read_string(&mut self, field: &str) -> Result<String, CustomError> {
+        let len = self.take(4, field)?;
+        let len = u32::from_be_bytes(len.try_into().unwrap_or_default()) as usize;
+        let bytes = self.take(len, field)?;
+
+        String::from_utf8(bytes.to_vec())
+            .map_err(|_| CustomError::Validation(format!("{} is not valid UTF-8.", field)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use mongodb::bson::oid::ObjectId;
+
+    use crate::{models::Licensee, utils::{CustomError, generate_key}, configs::LicenseeKeyring};
+
+    use super::{
+        encode_licensee_data, decode_licensee_data_v1, encrypt_licensee_data, decrypt_licensee_data, encode_licensee_commitment, decode_licensee_commitment,
+        LICENSEE_DATA_VERSION, LICENSEE_DATA_VERSION_ENCRYPTED
+    };
+
+    fn licensee() -> Licensee {
+        Licensee {
+            wallet_address: "0x8ba1f109551bd432803012645ac136ddd64dba72".to_string(),
+            name: "Jane Doe".to_string(),
+            dob: 631152000,
+            address: "1 Main Street".to_string(),
+            email_address: "jane@example.com".to_string(),
+            phone_number: "+6512345678".to_string(),
+            company: Some("Doe Holdings".to_string()),
+            nationality: "SG".to_string(),
+            country_of_application: "MY".to_string(),
+            usable: false,
+        }
+    }
+
+    /// Returns the message of a `Validation` error, panicking on any other result.
+    fn validation_error<T: std::fmt::Debug>(result: Result<T, CustomError>) -> String {
+        match result {
+            Err(CustomError::Validation(e)) => e,
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn licensee_data_round_trips() {
+        let encoded = encode_licensee_data(&licensee());
+
+        assert_eq!(encoded[0], LICENSEE_DATA_VERSION);
+        assert_eq!(decode_licensee_data_v1(&encoded[1..], false).unwrap(), licensee());
+    }
+
+    #[test]
+    fn licensee_data_round_trips_without_a_company() {
+        let licensee = Licensee { company: None, ..licensee() };
+        let encoded = encode_licensee_data(&licensee);
+
+        assert_eq!(decode_licensee_data_v1(&encoded[1..], false).unwrap(), licensee);
+    }
+
+    #[test]
+    fn licensee_data_round_trips_fields_containing_pipes() {
+        let licensee = Licensee {
+            name: "Jane | Doe".to_string(),
+            address: "Unit 1|2, Main Street".to_string(),
+            company: Some("|".to_string()),
+            ..licensee()
+        };
+        let encoded = encode_licensee_data(&licensee);
+
+        assert_eq!(decode_licensee_data_v1(&encoded[1..], false).unwrap(), licensee);
+    }
+
+    #[test]
+    fn licensee_data_keeps_the_given_usable_flag() {
+        let encoded = encode_licensee_data(&licensee());
+
+        assert!(decode_licensee_data_v1(&encoded[1..], true).unwrap().usable);
+    }
+
+    #[test]
+    fn truncated_licensee_data_names_the_truncated_field() {
+        let encoded = encode_licensee_data(&licensee());
+
+        let error = validation_error(decode_licensee_data_v1(&encoded[1..encoded.len() - 1], false));
+        assert!(error.contains("country_of_application"), "{}", error);
+
+        // cuts the data off within `dob`, right after the name.
+        let dob_offset = 1 + 4 + licensee().wallet_address.len() + 4 + licensee().name.len();
+        let error = validation_error(decode_licensee_data_v1(&encoded[1..dob_offset + 3], false));
+        assert!(error.contains("dob"), "{}", error);
+
+        let error = validation_error(decode_licensee_data_v1(&[], false));
+        assert!(error.contains("wallet_address"), "{}", error);
+    }
+
+    #[test]
+    fn licensee_data_with_trailing_bytes_is_rejected() {
+        let mut encoded = encode_licensee_data(&licensee());
+        encoded.push(0);
+
+        let error = validation_error(decode_licensee_data_v1(&encoded[1..], false));
+        assert!(error.contains("trailing"), "{}", error);
+    }
+
+    #[test]
+    fn licensee_data_with_invalid_utf8_is_rejected() {
+        let bytes = [&2_u32.to_be_bytes()[..], &[0xff, 0xfe]].concat();
+
+        let error = validation_error(decode_licensee_data_v1(&bytes, false));
+        assert!(error.contains("wallet_address"), "{}", error);
+    }
+
+    #[test]
+    fn licensee_commitment_round_trips() {
+        let record_id = ObjectId::new();
+        let commitment = [7_u8; 32];
+        let encoded = encode_licensee_commitment(&record_id, &commitment);
+
+        assert_eq!(decode_licensee_commitment(&encoded[1..]).unwrap(), (record_id, commitment));
+        assert!(decode_licensee_commitment(&encoded[1..encoded.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn encrypted_licensee_data_round_trips() {
+        let keyring = LicenseeKeyring::new(7, generate_key(), HashMap::new());
+        let encoded = encode_licensee_data(&licensee());
+        let encrypted = encrypt_licensee_data(&encoded, &licensee().wallet_address.to_uppercase(), &keyring);
+
+        assert_eq!(&encrypted[..2], &[LICENSEE_DATA_VERSION_ENCRYPTED, 7]);
+        assert_eq!(decrypt_licensee_data(&encrypted[1..], &licensee().wallet_address, &keyring).unwrap(), encoded);
+    }
+
+    #[test]
+    fn encrypted_licensee_data_survives_a_key_rotation() {
+        let old_key = generate_key();
+        let old_keyring = LicenseeKeyring::new(0, old_key, HashMap::new());
+        let encrypted = encrypt_licensee_data(&encode_licensee_data(&licensee()), &licensee().wallet_address, &old_keyring);
+
+        let rotated = LicenseeKeyring::new(1, generate_key(), HashMap::from([(0, old_key)]));
+        let decrypted = decrypt_licensee_data(&encrypted[1..], &licensee().wallet_address, &rotated).unwrap();
+        assert_eq!(decrypted, encode_licensee_data(&licensee()));
+
+        // new data is wrapped with the current key only.
+        let reencrypted = encrypt_licensee_data(&decrypted, &licensee().wallet_address, &rotated);
+        assert_eq!(reencrypted[1], 1);
+        assert!(decrypt_licensee_data(&reencrypted[1..], &licensee().wallet_address, &old_keyring).is_err());
+    }
+
+    #[test]
+    fn encrypted_licensee_data_with_an_unknown_key_is_rejected() {
+        let keyring = LicenseeKeyring::new(0, generate_key(), HashMap::new());
+        let mut encrypted = encrypt_licensee_data(&encode_licensee_data(&licensee()), &licensee().wallet_address, &keyring);
+        encrypted[1] = 9;
+
+        let error = validation_error(decrypt_licensee_data(&encrypted[1..], &licensee().wallet_address, &keyring));
+        assert!(error.contains("unknown key 9"), "{}", error);
+    }
+
+    #[test]
+    fn encrypted_licensee_data_is_bound_to_its_licensee() {
+        let keyring = LicenseeKeyring::new(0, generate_key(), HashMap::new());
+        let encrypted = encrypt_licensee_data(&encode_licensee_data(&licensee()), &licensee().wallet_address, &keyring);
+
+        assert!(decrypt_licensee_data(&encrypted[1..], "0x0000000000000000000000000000000000000001", &keyring).is_err());
+    }
+
+    #[test]
+    fn tampered_encrypted_licensee_data_is_rejected() {
+        let keyring = LicenseeKeyring::new(0, generate_key(), HashMap::new());
+        let encrypted = encrypt_licensee_data(&encode_licensee_data(&licensee()), &licensee().wallet_address, &keyring);
+
+        // flips a bit in the wrapped data key and in the sealed data in turn.
+        for index in [10, encrypted.len() - 10] {
+            let mut tampered = encrypted.clone();
+            tampered[index] ^= 1;
+
+            assert!(decrypt_licensee_data(&tampered[1..], &licensee().wallet_address, &keyring).is_err(), "byte {}", index);
+        }
+
+        assert!(decrypt_licensee_data(&encrypted[1..40], &licensee().wallet_address, &keyring).is_err());
+        assert!(decrypt_licensee_data(&[], &licensee().wallet_address, &keyring).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "reuses the current key's ID")]
+    fn keyrings_reject_a_retired_key_reusing_the_current_id() {
+        LicenseeKeyring::new(0, generate_key(), HashMap::from([(0, generate_key())]));
+    }
+}
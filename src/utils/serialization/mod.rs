@@ -1,3 +1,5 @@
 pub mod response;
+pub mod licensee;
 
-pub use response::*;
\ No newline at end of file
+pub use response::*;
+pub use licensee::*;
\ No newline at end of file
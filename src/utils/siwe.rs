@@ -0,0 +1,274 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use ethers::{types::{H160, Signature}, utils::to_checksum};
+
+use crate::{models::LoginUser, utils::CustomError, configs::{SIWE_ALLOWED_DOMAINS, MAX_SIWE_CLOCK_SKEW}};
+
+/// `SiweMessage` represents the fields of a Sign-In with Ethereum (EIP-4361) message.
+///
+/// The message is rebuilt server-side from the fields sent by the client so that the signature can be verified against it.
+#[derive(Debug, Clone)]
+pub struct SiweMessage {
+    /// the domain that is requesting the login
+    pub domain: String,
+    /// the wallet address that signed the message
+    pub wallet_address: String,
+    /// (optional) a human-readable statement that the user signed
+    pub statement: Option<String>,
+    /// the uri that the user is logging in from
+    pub uri: String,
+    /// the current version of SIWE (most likely will remain 1)
+    pub version: u8,
+    /// the id of the chain the user is on when they logged in
+    pub chain_id: u32,
+    /// a random string generated to prevent replay attacks
+    pub nonce: String,
+    /// when the message was issued (RFC3339 string, exactly as signed)
+    pub issued_at: String,
+    /// when the message expires (RFC3339 string, exactly as signed)
+    pub expiration_time: String,
+}
+
+impl SiweMessage {
+    /// Builds the `SiweMessage` that a login (or sign up) request claims to have signed.
+    pub fn from_login(login: &LoginUser) -> Self {
+        Self {
+            domain: login.domain.clone(),
+            wallet_address: login.wallet_address.clone(),
+            statement: login.statement.clone(),
+            uri: login.uri.clone(),
+            version: login.version,
+            chain_id: login.chain_id,
+            nonce: login.nonce.clone(),
+            issued_at: login.issued_at.clone(),
+            expiration_time: login.expiration_time.clone()
+        }
+    }
+
+    /// Parses the message's expiration time into a UNIX timestamp.
+    pub fn expiration_date(&self) -> Result<i64, CustomError> {
+        DateTime::parse_from_rfc3339(&self.expiration_time)
+            .map(|expiration_time| expiration_time.timestamp())
+            .map_err(|e| CustomError::Auth(format!("Expiration time is not RFC3339 compliant: {}", e)))
+    }
+
+    /// Parses the message's issuance time into a UNIX timestamp.
+    pub fn issued_date(&self) -> Result<i64, CustomError> {
+        DateTime::parse_from_rfc3339(&self.issued_at)
+            .map(|issued_at| issued_at.timestamp())
+            .map_err(|e| CustomError::Auth(format!("Issuance time is not RFC3339 compliant: {}", e)))
+    }
+
+    /// Builds the EIP-4361 message string that the user is expected to have signed.
+    ///
+    /// NOTE: the wallet address is converted to its EIP-55 checksummed form, as required by the standard.
+    pub fn to_message(&self) -> Result<String, CustomError> {
+        let address = H160::from_str(&self.wallet_address)
+            .map_err(|e| CustomError::Auth(format!("Invalid wallet address: {}", e)))?;
+
+        // the statement is optional; if it doesn't exist, an empty line is left in its place.
+        let statement = match &self.statement {
+            Some(statement) => format!("{}\n", statement),
+            None => "".to_string(),
+        };
+
+        Ok(format!(
+            "{} wants you to sign in with your Ethereum account:\n{}\n\n{}\nURI: {}\nVersion: {}\nChain ID: {}\nNonce: {}\nIssued At: {}\nExpiration Time: {}",
+            self.domain,
+            to_checksum(&address, None),
+            statement,
+            self.uri,
+            self.version,
+            self.chain_id,
+            self.nonce,
+            self.issued_at,
+            self.expiration_time
+        ))
+    }
+
+    /// Verifies that `signature` was produced by `wallet_address` signing this message.
+    ///
+    /// Also checks that the domain is allowed (see `SIWE_ALLOWED_DOMAINS`), that the message wasn't issued in the future
+    /// (give or take `MAX_SIWE_CLOCK_SKEW`) and that it has not yet expired.
+    pub fn verify(&self, signature: &str) -> Result<(), CustomError> {
+        if !SIWE_ALLOWED_DOMAINS.iter().any(|domain| domain == &self.domain) {
+            return Err(CustomError::Auth(format!("Domain {} is not allowed.", self.domain)));
+        }
+
+        if self.issued_date()? > Utc::now().timestamp() + MAX_SIWE_CLOCK_SKEW {
+            return Err(CustomError::Auth("Message was issued in the future.".to_string()));
+        }
+
+        if self.expiration_date()? <= Utc::now().timestamp() {
+            return Err(CustomError::Auth("Message has expired.".to_string()));
+        }
+
+        let message = self.to_message()?;
+        let signature = Signature::from_str(signature)
+            .map_err(|e| CustomError::Auth(format!("Invalid signature: {}", e)))?;
+        let recovered = signature.recover(message)
+            .map_err(|e| CustomError::Auth(format!("Failed to recover signer: {}", e)))?;
+
+        let wallet_address = H160::from_str(&self.wallet_address)
+            .map_err(|e| CustomError::Auth(format!("Invalid wallet address: {}", e)))?;
+
+        if recovered != wallet_address {
+            return Err(CustomError::Auth("Signature does not match the wallet address.".to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, SecondsFormat, Utc};
+    use ethers::{core::rand::thread_rng, signers::{LocalWallet, Signer}};
+
+    use crate::utils::testing::{init_test_env, TEST_DOMAIN};
+
+    use super::SiweMessage;
+
+    fn message() -> SiweMessage {
+        SiweMessage {
+            domain: "example.com".to_string(),
+            wallet_address: "0x8ba1f109551bd432803012645ac136ddd64dba72".to_string(),
+            statement: Some("Sign in to the NBC licensing API.".to_string()),
+            uri: "https://example.com/login".to_string(),
+            version: 1,
+            chain_id: 97,
+            nonce: "abcdefghijklmnopq".to_string(),
+            issued_at: "2024-01-01T00:00:00.000Z".to_string(),
+            expiration_time: "2024-01-01T01:00:00.000Z".to_string(),
+        }
+    }
+
+    /// Builds a message for `wallet` that expires in an hour, along with the wallet's signature of it.
+    async fn signed_message(wallet: &LocalWallet) -> (SiweMessage, String) {
+        let now = Utc::now();
+        let message = SiweMessage {
+            domain: TEST_DOMAIN.to_string(),
+            wallet_address: format!("{:?}", wallet.address()),
+            issued_at: now.to_rfc3339_opts(SecondsFormat::Millis, true),
+            expiration_time: (now + Duration::hours(1)).to_rfc3339_opts(SecondsFormat::Millis, true),
+            ..message()
+        };
+        let signature = wallet.sign_message(message.to_message().unwrap()).await.unwrap().to_string();
+
+        (message, signature)
+    }
+
+    #[test]
+    fn to_message_follows_eip_4361() {
+        assert_eq!(
+            message().to_message().unwrap(),
+            "example.com wants you to sign in with your Ethereum account:\n\
+            0x8ba1f109551bD432803012645Ac136ddd64DBA72\n\
+            \n\
+            Sign in to the NBC licensing API.\n\
+            \n\
+            URI: https://example.com/login\n\
+            Version: 1\n\
+            Chain ID: 97\n\
+            Nonce: abcdefghijklmnopq\n\
+            Issued At: 2024-01-01T00:00:00.000Z\n\
+            Expiration Time: 2024-01-01T01:00:00.000Z"
+        );
+    }
+
+    #[test]
+    fn to_message_leaves_an_empty_line_without_a_statement() {
+        let message = SiweMessage { statement: None, ..message() }.to_message().unwrap();
+
+        assert!(message.contains("0x8ba1f109551bD432803012645Ac136ddd64DBA72\n\n\nURI: "), "{}", message);
+    }
+
+    #[test]
+    fn to_message_rejects_an_invalid_wallet_address() {
+        assert!(SiweMessage { wallet_address: "0x1234".to_string(), ..message() }.to_message().is_err());
+    }
+
+    #[test]
+    fn issued_date_is_parsed_from_the_signed_string() {
+        assert_eq!(message().issued_date().unwrap(), 1704067200);
+        assert!(SiweMessage { issued_at: "today".to_string(), ..message() }.issued_date().is_err());
+    }
+
+    #[test]
+    fn expiration_date_is_parsed_from_the_signed_string() {
+        assert_eq!(message().expiration_date().unwrap(), 1704070800);
+        assert!(SiweMessage { expiration_time: "tomorrow".to_string(), ..message() }.expiration_date().is_err());
+    }
+
+    #[tokio::test]
+    async fn verify_accepts_a_valid_signature() {
+        init_test_env();
+
+        let wallet = LocalWallet::new(&mut thread_rng());
+        let (message, signature) = signed_message(&wallet).await;
+
+        assert!(message.verify(&signature).is_ok());
+    }
+
+    #[tokio::test]
+    async fn verify_rejects_a_signature_from_another_wallet() {
+        init_test_env();
+
+        let wallet = LocalWallet::new(&mut thread_rng());
+        let other_wallet = LocalWallet::new(&mut thread_rng());
+        let (message, _) = signed_message(&wallet).await;
+        let (_, signature) = signed_message(&other_wallet).await;
+
+        assert!(message.verify(&signature).is_err());
+    }
+
+    #[tokio::test]
+    async fn verify_rejects_a_domain_that_is_not_allowed() {
+        init_test_env();
+
+        let wallet = LocalWallet::new(&mut thread_rng());
+        let (message, _) = signed_message(&wallet).await;
+        let message = SiweMessage { domain: "evil.example.com".to_string(), ..message };
+        let signature = wallet.sign_message(message.to_message().unwrap()).await.unwrap().to_string();
+
+        assert!(message.verify(&signature).is_err());
+    }
+
+    #[tokio::test]
+    async fn verify_rejects_an_expired_message() {
+        init_test_env();
+
+        let wallet = LocalWallet::new(&mut thread_rng());
+        let (message, _) = signed_message(&wallet).await;
+        let message = SiweMessage { expiration_time: "2024-01-01T01:00:00.000Z".to_string(), ..message };
+        let signature = wallet.sign_message(message.to_message().unwrap()).await.unwrap().to_string();
+
+        assert!(message.verify(&signature).is_err());
+    }
+
+    #[tokio::test]
+    async fn verify_rejects_a_message_issued_in_the_future() {
+        init_test_env();
+
+        let wallet = LocalWallet::new(&mut thread_rng());
+        let (message, _) = signed_message(&wallet).await;
+        let issued_at = (Utc::now() + Duration::minutes(10)).to_rfc3339_opts(SecondsFormat::Millis, true);
+        let message = SiweMessage { issued_at, ..message };
+        let signature = wallet.sign_message(message.to_message().unwrap()).await.unwrap().to_string();
+
+        assert!(message.verify(&signature).is_err());
+    }
+
+    #[tokio::test]
+    async fn verify_rejects_an_unparseable_issuance_time() {
+        init_test_env();
+
+        let wallet = LocalWallet::new(&mut thread_rng());
+        let (message, _) = signed_message(&wallet).await;
+        let message = SiweMessage { issued_at: "just now".to_string(), ..message };
+        let signature = wallet.sign_message(message.to_message().unwrap()).await.unwrap().to_string();
+
+        assert!(message.verify(&signature).is_err());
+    }
+}
@@ -0,0 +1,103 @@
+use std::{env, sync::Once};
+
+use axum::http::{header::AUTHORIZATION, request::Parts, Request};
+use chrono::{Duration, SecondsFormat, Utc};
+use ethers::{core::rand::thread_rng, signers::{LocalWallet, Signer}};
+
+use crate::{models::LoginUser, api::{generate_nonce, create_user}, configs::AppState, utils::{SiweMessage, AuthenticatedUser}};
+
+/// the domain that test logins are signed for
+pub const TEST_DOMAIN: &str = "localhost:3000";
+/// the ID of the current licensee encryption key (see `LICENSEE_ENCRYPTION_KEYS`)
+pub const TEST_ENCRYPTION_KEY_ID: u8 = 1;
+/// the licensee encryption key that `TEST_ENCRYPTION_KEY_ID` replaced, under ID 0
+pub const TEST_RETIRED_ENCRYPTION_KEY: &str = "0101010101010101010101010101010101010101010101010101010101010101";
+
+static TEST_ENV: Once = Once::new();
+
+/// Sets the environment variables read by the handlers, so that tests don't depend on a `.env` file.
+///
+/// NOTE: the License contract is pointed at an unreachable RPC URL, so that on-chain lookups fail fast instead of reaching a real chain.
+/// Licensee data is encrypted, with a keyring that has been rotated once.
+pub fn init_test_env() {
+    TEST_ENV.call_once(|| {
+        env::set_var("SIWE_ALLOWED_DOMAINS", TEST_DOMAIN);
+        env::set_var("ACCESS_TOKEN_SECRET", "test-access-token-secret-of-32-bytes");
+        env::set_var("LICENSE_ADDRESS", "0x0000000000000000000000000000000000000001");
+        env::set_var("BNB_TESTNET", "http://127.0.0.1:1");
+        env::set_var("SECONDARY_DEPLOYER_WALLET_PVT_KEY", "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80");
+        env::set_var("LICENSEE_ENCRYPTION_KEY", "0202020202020202020202020202020202020202020202020202020202020202");
+        env::set_var("LICENSEE_ENCRYPTION_KEY_ID", TEST_ENCRYPTION_KEY_ID.to_string());
+        env::set_var("LICENSEE_RETIRED_ENCRYPTION_KEYS", format!("0:{}", TEST_RETIRED_ENCRYPTION_KEY));
+    });
+}
+
+/// Builds a login request over `nonce` and signs its SIWE message with `wallet`.
+pub async fn signed_login(wallet: &LocalWallet, nonce: String) -> LoginUser {
+    let now = Utc::now();
+
+    let login = LoginUser {
+        wallet_address: format!("{:?}", wallet.address()),
+        expiration_time: (now + Duration::hours(1)).to_rfc3339_opts(SecondsFormat::Secs, true),
+        chain_id: 97,
+        domain: TEST_DOMAIN.to_string(),
+        nonce,
+        signature: String::new(),
+        payload: None,
+        profile_id: "test-profile".to_string(),
+        uri: format!("http://{}", TEST_DOMAIN),
+        version: 1,
+        statement: Some("Sign in to the NBC licensing API.".to_string()),
+        issued_at: now.to_rfc3339_opts(SecondsFormat::Secs, true),
+    };
+
+    sign_login(wallet, login).await
+}
+
+/// (Re-)signs the SIWE message of a login request with `wallet`, e.g. after changing its fields.
+pub async fn sign_login(wallet: &LocalWallet, mut login: LoginUser) -> LoginUser {
+    let message = SiweMessage::from_login(&login).to_message().unwrap();
+    login.signature = wallet.sign_message(message).await.unwrap().to_string();
+
+    login
+}
+
+/// Issues a nonce from `state` and builds a login request over it, signed by `wallet`.
+pub async fn issued_login(state: &AppState, wallet: &LocalWallet) -> LoginUser {
+    let nonce = generate_nonce(state.nonces.as_ref()).await.unwrap();
+
+    signed_login(wallet, nonce).await
+}
+
+/// Signs up a user with a random wallet, returning the wallet and the user authenticated by the new session.
+pub async fn sign_up(state: &AppState) -> (LocalWallet, AuthenticatedUser) {
+    let (wallet, auth, _) = sign_up_with_token(state).await;
+
+    (wallet, auth)
+}
+
+/// Like `sign_up`, but also returns the new session's bearer token.
+pub async fn sign_up_with_token(state: &AppState) -> (LocalWallet, AuthenticatedUser, String) {
+    init_test_env();
+
+    let wallet = LocalWallet::new(&mut thread_rng());
+    let login = issued_login(state, &wallet).await;
+    let (_, session, token) = create_user(state.nonces.as_ref(), state.users.as_ref(), state.sessions.as_ref(), login).await.unwrap();
+
+    let auth = AuthenticatedUser {
+        wallet_address: session.wallet_address,
+        session_id: session._id,
+    };
+
+    (wallet, auth, token)
+}
+
+/// Builds the parts of a request authenticated by the given bearer token, to run extractors against.
+pub fn bearer_parts(token: &str) -> Parts {
+    Request::builder()
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .body(())
+        .unwrap()
+        .into_parts()
+        .0
+}
@@ -0,0 +1,152 @@
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::CustomError;
+
+/// `DateInput` represents a date that can be given either as a UNIX timestamp or an RFC3339 string.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DateInput {
+    /// a UNIX timestamp (in seconds)
+    Unix(i64),
+    /// an RFC3339 compliant date string
+    Rfc3339(String),
+}
+
+impl DateInput {
+    /// Converts the date into a UNIX timestamp.
+    pub fn to_timestamp(&self, field: &str) -> Result<i64, CustomError> {
+        match self {
+            DateInput::Unix(timestamp) => Ok(*timestamp),
+            DateInput::Rfc3339(date) => DateTime::parse_from_rfc3339(date)
+                .map(|date| date.timestamp())
+                .map_err(|e| CustomError::Validation(format!("{} is not RFC3339 compliant: {}", field, e)))
+        }
+    }
+}
+
+/// Checks whether `email` is a syntactically valid email address.
+/// 
+/// NOTE: this is a pragmatic check (single `@`, non-empty local part, dotted domain, no whitespace) rather than full RFC5322 validation.
+pub fn is_valid_email(email: &str) -> bool {
+    if email.len() > 254 || email.chars().any(|c| c.is_whitespace()) {
+        return false;
+    }
+
+    match email.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && !domain.contains('@')
+                && domain.split('.').count() >= 2
+                && domain.split('.').all(|label| !label.is_empty())
+        },
+        None => false
+    }
+}
+
+/// Checks whether `phone` is an E.164 compliant phone number (e.g. `+6512345678`).
+pub fn is_valid_e164_phone(phone: &str) -> bool {
+    match phone.strip_prefix('+') {
+        Some(digits) => {
+            (2..=15).contains(&digits.len())
+                && digits.chars().all(|c| c.is_ascii_digit())
+                && !digits.starts_with('0')
+        },
+        None => false
+    }
+}
+
+/// Checks whether `code` is an (uppercase) ISO 3166-1 alpha-2 country code (e.g. `SG`).
+pub fn is_valid_country_code(code: &str) -> bool {
+    ISO_3166_ALPHA2.binary_search(&code).is_ok()
+}
+
+/// the officially assigned ISO 3166-1 alpha-2 country codes, sorted so that they can be binary searched
+const ISO_3166_ALPHA2: [&str; 249] = [
+    "AD", "AE", "AF", "AG", "AI", "AL", "AM", "AO", "AQ", "AR", "AS", "AT", "AU", "AW", "AX", "AZ",
+    "BA", "BB", "BD", "BE", "BF", "BG", "BH", "BI", "BJ", "BL", "BM", "BN", "BO", "BQ", "BR", "BS", "BT", "BV", "BW", "BY", "BZ",
+    "CA", "CC", "CD", "CF", "CG", "CH", "CI", "CK", "CL", "CM", "CN", "CO", "CR", "CU", "CV", "CW", "CX", "CY", "CZ",
+    "DE", "DJ", "DK", "DM", "DO", "DZ",
+    "EC", "EE", "EG", "EH", "ER", "ES", "ET",
+    "FI", "FJ", "FK", "FM", "FO", "FR",
+    "GA", "GB", "GD", "GE", "GF", "GG", "GH", "GI", "GL", "GM", "GN", "GP", "GQ", "GR", "GS", "GT", "GU", "GW", "GY",
+    "HK", "HM", "HN", "HR", "HT", "HU",
+    "ID", "IE", "IL", "IM", "IN", "IO", "IQ", "IR", "IS", "IT",
+    "JE", "JM", "JO", "JP",
+    "KE", "KG", "KH", "KI", "KM", "KN", "KP", "KR", "KW", "KY", "KZ",
+    "LA", "LB", "LC", "LI", "LK", "LR", "LS", "LT", "LU", "LV", "LY",
+    "MA", "MC", "MD", "ME", "MF", "MG", "MH", "MK", "ML", "MM", "MN", "MO", "MP", "MQ", "MR", "MS", "MT", "MU", "MV", "MW", "MX", "MY", "MZ",
+    "NA", "NC", "NE", "NF", "NG", "NI", "NL", "NO", "NP", "NR", "NU", "NZ",
+    "OM",
+    "PA", "PE", "PF", "PG", "PH", "PK", "PL", "PM", "PN", "PR", "PS", "PT", "PW", "PY",
+    "QA",
+    "RE", "RO", "RS", "RU", "RW",
+    "SA", "SB", "SC", "SD", "SE", "SG", "SH", "SI", "SJ", "SK", "SL", "SM", "SN", "SO", "SR", "SS", "ST", "SV", "SX", "SY", "SZ",
+    "TC", "TD", "TF", "TG", "TH", "TJ", "TK", "TL", "TM", "TN", "TO", "TR", "TT", "TV", "TW", "TZ",
+    "UA", "UG", "UM", "US", "UY", "UZ",
+    "VA", "VC", "VE", "VG", "VI", "VN", "VU",
+    "WF", "WS",
+    "YE", "YT",
+    "ZA", "ZM", "ZW",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::{is_valid_email, is_valid_e164_phone, is_valid_country_code, DateInput, ISO_3166_ALPHA2};
+
+    #[test]
+    fn accepts_valid_emails() {
+        for email in ["jane@example.com", "jane.doe+licensing@mail.example.co.uk", "j@e.io"] {
+            assert!(is_valid_email(email), "{}", email);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_emails() {
+        let too_long = format!("{}@example.com", "a".repeat(250));
+
+        for email in ["", "jane", "@example.com", "jane@", "jane@example", "jane@@example.com", "jane@exa@mple.com", "jane@example..com", "jane doe@example.com", &too_long] {
+            assert!(!is_valid_email(email), "{}", email);
+        }
+    }
+
+    #[test]
+    fn accepts_e164_phone_numbers() {
+        for phone in ["+6512345678", "+12", "+123456789012345"] {
+            assert!(is_valid_e164_phone(phone), "{}", phone);
+        }
+    }
+
+    #[test]
+    fn rejects_phone_numbers_that_are_not_e164() {
+        for phone in ["", "+", "6512345678", "+1", "+1234567890123456", "+0612345678", "+65 1234 5678", "+65-12345678"] {
+            assert!(!is_valid_e164_phone(phone), "{}", phone);
+        }
+    }
+
+    #[test]
+    fn accepts_iso_3166_alpha2_country_codes() {
+        for code in ["SG", "MY", "US", "AD", "ZW"] {
+            assert!(is_valid_country_code(code), "{}", code);
+        }
+    }
+
+    #[test]
+    fn rejects_country_codes_that_are_not_iso_3166_alpha2() {
+        for code in ["", "sg", "SGP", "XX", "UK", "S"] {
+            assert!(!is_valid_country_code(code), "{}", code);
+        }
+    }
+
+    #[test]
+    fn country_codes_are_sorted_for_binary_search() {
+        assert!(ISO_3166_ALPHA2.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn date_inputs_convert_to_timestamps() {
+        assert_eq!(DateInput::Unix(631152000).to_timestamp("dob").unwrap(), 631152000);
+        assert_eq!(DateInput::Rfc3339("1990-01-01T08:00:00+08:00".to_string()).to_timestamp("dob").unwrap(), 631152000);
+        assert!(DateInput::Rfc3339("01/01/1990".to_string()).to_timestamp("dob").is_err());
+    }
+}